@@ -0,0 +1,185 @@
+use blame_rs::{BlameOptions, BlameRevision, DiffAlgorithm, blame_with_options};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::rc::Rc;
+
+const ALGORITHMS: [(DiffAlgorithm, &str); 2] = [
+    (DiffAlgorithm::Myers, "myers"),
+    (DiffAlgorithm::Patience, "patience"),
+];
+
+/// Deterministic xorshift generator so corpora are identical across runs
+struct Corpus {
+    state: u64,
+    next_id: u64,
+}
+
+impl Corpus {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            next_id: 0,
+        }
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % n.max(1) as u64) as usize
+    }
+
+    /// A fresh, source-code-like line that never repeats
+    fn line(&mut self) -> String {
+        self.next_id += 1;
+        let id = self.next_id;
+        match id % 4 {
+            0 => format!("    let value_{} = compute({});\n", id, id * 7),
+            1 => format!("    if value_{} > {} {{\n", id, id % 97),
+            2 => format!("        log::debug!(\"step {}\");\n", id),
+            _ => "    }\n".to_string(),
+        }
+    }
+
+    fn file(&mut self, lines: usize) -> Vec<String> {
+        (0..lines).map(|_| self.line()).collect()
+    }
+
+    /// Applies `edits` random single-line replacements, insertions or deletions
+    fn edit(&mut self, lines: &mut Vec<String>, edits: usize) {
+        for _ in 0..edits {
+            let at = self.below(lines.len() + 1);
+            match self.below(3) {
+                0 if at < lines.len() => lines[at] = self.line(),
+                1 if at < lines.len() => {
+                    lines.remove(at);
+                }
+                _ => {
+                    let line = self.line();
+                    lines.insert(at, line);
+                }
+            }
+        }
+    }
+
+    /// A history starting from `lines` lines, each later revision applying `edits` edits
+    fn history(&mut self, lines: usize, revisions: usize, edits: usize) -> Vec<String> {
+        let mut current = self.file(lines);
+        let mut history = Vec::with_capacity(revisions);
+        history.push(current.concat());
+        for _ in 1..revisions {
+            self.edit(&mut current, edits);
+            history.push(current.concat());
+        }
+        history
+    }
+}
+
+fn to_revisions(contents: &[String]) -> Vec<BlameRevision<'_, usize>> {
+    contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| BlameRevision {
+            content,
+            metadata: Rc::new(idx),
+        })
+        .collect()
+}
+
+fn bench_corpus(c: &mut Criterion, group_name: &str, cases: &[(String, Vec<String>)]) {
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+
+    for (case, contents) in cases {
+        let revisions = to_revisions(contents);
+        group.throughput(Throughput::Elements(revisions.len() as u64));
+
+        for (algorithm, algorithm_name) in ALGORITHMS {
+            let options = BlameOptions {
+                algorithm,
+                ..Default::default()
+            };
+            group.bench_with_input(
+                BenchmarkId::new(algorithm_name, case),
+                &revisions,
+                |b, revisions| {
+                    b.iter(|| blame_with_options(black_box(revisions), options).unwrap());
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Thousands of small commits to a medium-sized file
+fn long_history(c: &mut Criterion) {
+    let mut corpus = Corpus::new(0x5eed_0001);
+    let cases: Vec<(String, Vec<String>)> = [1_000, 5_000]
+        .into_iter()
+        .map(|revisions| {
+            (
+                format!("{}_revisions", revisions),
+                corpus.history(300, revisions, 3),
+            )
+        })
+        .collect();
+
+    bench_corpus(c, "long_history", &cases);
+}
+
+/// Very large files with a handful of scattered edits per revision
+fn huge_file(c: &mut Criterion) {
+    let mut corpus = Corpus::new(0x5eed_0002);
+    let cases: Vec<(String, Vec<String>)> = [20_000, 100_000]
+        .into_iter()
+        .map(|lines| (format!("{}_lines", lines), corpus.history(lines, 10, 20)))
+        .collect();
+
+    bench_corpus(c, "huge_file", &cases);
+}
+
+/// Inputs that defeat common diff shortcuts
+fn pathological(c: &mut Criterion) {
+    let mut corpus = Corpus::new(0x5eed_0003);
+
+    // Few distinct lines: Patience has no unique anchors, Myers sees many equal candidates
+    let mut repetitive: Vec<String> = (0..5_000)
+        .map(|_| ["{\n", "}\n", "\n"][corpus.below(3)].to_string())
+        .collect();
+    let mut repetitive_history = vec![repetitive.concat()];
+    for _ in 1..20 {
+        for _ in 0..50 {
+            let at = corpus.below(repetitive.len());
+            repetitive[at] = ["{\n", "}\n", "\n"][corpus.below(3)].to_string();
+        }
+        repetitive_history.push(repetitive.concat());
+    }
+
+    // Every revision shares no lines with the previous one
+    let full_rewrite: Vec<String> = (0..20).map(|_| corpus.file(2_000).concat()).collect();
+
+    // Alternating between a file and its reverse
+    let forward = corpus.file(2_000);
+    let backward: Vec<String> = forward.iter().rev().cloned().collect();
+    let reversed: Vec<String> = (0..20)
+        .map(|i| {
+            if i % 2 == 0 {
+                forward.concat()
+            } else {
+                backward.concat()
+            }
+        })
+        .collect();
+
+    let cases = vec![
+        ("repetitive".to_string(), repetitive_history),
+        ("full_rewrite".to_string(), full_rewrite),
+        ("reversed".to_string(), reversed),
+    ];
+
+    bench_corpus(c, "pathological", &cases);
+}
+
+criterion_group!(benches, long_history, huge_file, pathological);
+criterion_main!(benches);
@@ -49,6 +49,7 @@ fn main() {
         &revisions,
         BlameOptions {
             algorithm: Patience,
+            ..Default::default()
         },
     )
     .expect("Blame operation failed");
@@ -132,6 +132,7 @@ fn main() {
         &revisions,
         BlameOptions {
             algorithm: Patience,
+            ..Default::default()
         },
     )
     .expect("Blame operation failed");
@@ -0,0 +1,83 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use blame_rs::{BlameOptions, BlameRevision, DiffAlgorithm, blame_with_options};
+use libfuzzer_sys::fuzz_target;
+use std::rc::Rc;
+
+/// Line terminators mixed within and across revisions
+#[derive(Debug, Clone, Copy, Arbitrary)]
+enum Terminator {
+    Lf,
+    CrLf,
+    Cr,
+    None,
+}
+
+// A tiny alphabet keeps lines colliding so diffs stay ambiguous
+const WORDS: [&str; 4] = ["", "a", "b", "a b"];
+
+#[derive(Debug, Arbitrary)]
+struct Line {
+    word: u8,
+    terminator: Terminator,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    patience: bool,
+    revisions: Vec<Vec<Line>>,
+}
+
+fn render(lines: &[Line]) -> String {
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(WORDS[usize::from(line.word) % WORDS.len()]);
+        content.push_str(match line.terminator {
+            Terminator::Lf => "\n",
+            Terminator::CrLf => "\r\n",
+            Terminator::Cr => "\r",
+            Terminator::None => "",
+        });
+    }
+    content
+}
+
+fuzz_target!(|input: Input| {
+    if input.revisions.is_empty() {
+        return;
+    }
+
+    let algorithm = if input.patience {
+        DiffAlgorithm::Patience
+    } else {
+        DiffAlgorithm::Myers
+    };
+
+    let contents: Vec<String> = input.revisions.iter().map(|lines| render(lines)).collect();
+    let revisions: Vec<BlameRevision<usize>> = contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| BlameRevision {
+            content,
+            metadata: Rc::new(idx),
+        })
+        .collect();
+
+    let result = blame_with_options(
+        &revisions,
+        BlameOptions {
+            algorithm,
+            ..Default::default()
+        },
+    )
+    .expect("non-empty revisions should always blame");
+
+    let reconstructed: String = result.iter().map(|line| line.content).collect();
+    assert_eq!(&reconstructed, contents.last().unwrap());
+
+    for (idx, line) in result.iter().enumerate() {
+        assert_eq!(line.line_number, idx);
+        assert!(line.revision_index < contents.len());
+    }
+});
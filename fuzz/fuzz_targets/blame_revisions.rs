@@ -0,0 +1,49 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use blame_rs::{BlameError, BlameOptions, BlameRevision, DiffAlgorithm, blame_with_options};
+use libfuzzer_sys::fuzz_target;
+use std::rc::Rc;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    patience: bool,
+    revisions: Vec<String>,
+}
+
+fuzz_target!(|input: Input| {
+    let algorithm = if input.patience {
+        DiffAlgorithm::Patience
+    } else {
+        DiffAlgorithm::Myers
+    };
+
+    let revisions: Vec<BlameRevision<usize>> = input
+        .revisions
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| BlameRevision {
+            content,
+            metadata: Rc::new(idx),
+        })
+        .collect();
+
+    match blame_with_options(
+        &revisions,
+        BlameOptions {
+            algorithm,
+            ..Default::default()
+        },
+    ) {
+        Ok(result) => {
+            let reconstructed: String = result.iter().map(|line| line.content).collect();
+            assert_eq!(&reconstructed, input.revisions.last().unwrap());
+
+            for line in result.iter() {
+                assert_eq!(*line.revision_metadata, line.revision_index);
+            }
+        }
+        Err(BlameError::EmptyRevisions) => assert!(revisions.is_empty()),
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+});
@@ -0,0 +1,310 @@
+use crate::blame::{
+    HistoryValidator, Origin, Pairing, Previous, Step, StepDiffer, Tracked, Unit,
+    check_revision_count, check_units, report_progress,
+};
+use crate::types::{
+    BlameError, BlameOptions, BlameReport, DiffInvariant, HistoryWarning, Progress,
+};
+use similar::DiffTag;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+/// A unit whose origin the backward walk is looking for
+struct Slot<'a, U: ?Sized> {
+    /// Content as of the revision the unit was tracked from
+    content: &'a U,
+    /// Revision and unit index that introduced the unit, once reached
+    origin: Option<(usize, usize)>,
+    /// Revision, unit index and slot of the unit it replaced, if paired by similarity
+    previous: Option<(usize, usize, usize)>,
+}
+
+/// Units deleted and inserted since the last unchanged unit, and the pending units among the
+/// inserted ones as `(unit index, slot)`
+#[derive(Default)]
+struct Hunk {
+    deleted: Vec<usize>,
+    inserted: Vec<usize>,
+    introduced: Vec<(usize, usize)>,
+}
+
+impl Hunk {
+    fn clear(&mut self) {
+        self.deleted.clear();
+        self.inserted.clear();
+        self.introduced.clear();
+    }
+}
+
+/// Tracks the origin of every unit of the final revision by walking the history backward.
+///
+/// `revision(k)` splits revision `k` into units and returns them with its metadata; it is
+/// only called for the revisions the walk reaches. The walk stops once every final unit, and
+/// every unit one of them was paired with, is attributed, unless empty revisions have to be
+/// reported. `on_progress` is called after each revision and may cancel the remaining steps.
+pub(crate) fn track_backward<'a, T, U, F>(
+    count: usize,
+    revision: F,
+    options: BlameOptions,
+    on_progress: &mut dyn FnMut(Progress) -> ControlFlow<()>,
+) -> Result<Tracked<'a, T, U>, BlameError>
+where
+    U: Unit + ?Sized,
+    F: Fn(usize) -> (Vec<&'a U>, Rc<T>),
+{
+    check_revision_count(count, &options.limits)?;
+    let differ = StepDiffer::new(&options);
+    let warnings = if options.validate {
+        validate(count, &revision)
+    } else {
+        Vec::new()
+    };
+
+    let last = count - 1;
+    let (mut units, last_metadata) = revision(last);
+    check_units(last, &units, differ.limits())?;
+    let final_len = units.len();
+
+    // Metadata of every revision reached so far, newest first
+    let mut metadata = vec![last_metadata];
+    let mut slots: Vec<Slot<'a, U>> = units
+        .iter()
+        .map(|&content| Slot {
+            content,
+            origin: None,
+            previous: None,
+        })
+        .collect();
+    // Unattributed units of the current revision as `(unit index, slot)`, in unit order
+    let mut pending: Vec<(usize, usize)> = (0..units.len()).map(|index| (index, index)).collect();
+    let mut approximated_revisions = Vec::new();
+    let mut empty_revisions = Vec::new();
+
+    let mut revision_index = last;
+    report_progress(on_progress, 1, count, last.saturating_sub(1))?;
+
+    while revision_index > 0 && (options.report_empty_revisions || !pending.is_empty()) {
+        let old_revision = revision_index - 1;
+        let (old_units, old_metadata) = revision(old_revision);
+        check_units(old_revision, &old_units, differ.limits())?;
+
+        let step = differ.step(
+            revision_index,
+            &old_units,
+            &units,
+            options.similarity_threshold,
+        )?;
+        if let Some(Step {
+            ops: diff_ops,
+            mut approximated,
+            mut pairing,
+        }) = step
+        {
+            let mut remaining = std::mem::take(&mut pending).into_iter().peekable();
+            let mut hunk = Hunk::default();
+            let mut added = false;
+
+            for op in &diff_ops {
+                let (tag, old_range, new_range) = op.as_tag_tuple();
+                if tag == DiffTag::Equal {
+                    if let Some(pairing) = &mut pairing {
+                        link_replaced(
+                            &hunk,
+                            &old_units,
+                            &units,
+                            old_revision,
+                            pairing,
+                            &mut slots,
+                            &mut pending,
+                        );
+                    }
+                    hunk.clear();
+
+                    if old_range.end > old_units.len() {
+                        return Err(BlameError::DiffInvariantViolated {
+                            revision_index,
+                            cause: DiffInvariant::OldIndexOutOfBounds {
+                                old_index: old_range.start.max(old_units.len()),
+                                len: old_units.len(),
+                            },
+                        });
+                    }
+
+                    // Unchanged units are looked up in the older revision instead
+                    while let Some((index, slot)) =
+                        remaining.next_if(|&(index, _)| index < new_range.end)
+                    {
+                        pending.push((old_range.start + index - new_range.start, slot));
+                    }
+                    continue;
+                }
+
+                hunk.deleted.extend(old_range);
+                added |= !new_range.is_empty();
+                while let Some((index, slot)) =
+                    remaining.next_if(|&(index, _)| index < new_range.end)
+                {
+                    slots[slot].origin = Some((revision_index, index));
+                    hunk.introduced.push((index, slot));
+                }
+                hunk.inserted.extend(new_range);
+            }
+
+            if let Some(pairing) = &mut pairing {
+                link_replaced(
+                    &hunk,
+                    &old_units,
+                    &units,
+                    old_revision,
+                    pairing,
+                    &mut slots,
+                    &mut pending,
+                );
+            }
+            approximated |= differ.finish_pairing(revision_index, pairing.as_ref())?;
+            // Linked units join the carried-over ones out of order
+            pending.sort_unstable();
+
+            if approximated {
+                approximated_revisions.push(revision_index);
+            }
+            if !added {
+                empty_revisions.push(revision_index);
+            }
+        } else {
+            // Nothing to diff: every pending unit carries over unchanged
+            empty_revisions.push(revision_index);
+        }
+
+        units = old_units;
+        metadata.push(old_metadata);
+        revision_index = old_revision;
+        report_progress(
+            on_progress,
+            count - revision_index,
+            count,
+            revision_index.saturating_sub(1),
+        )?;
+    }
+
+    // Whatever is still pending was already there in the oldest revision reached
+    for &(index, slot) in &pending {
+        slots[slot].origin = Some((revision_index, index));
+    }
+    if revision_index == 0 && units.is_empty() {
+        empty_revisions.push(0);
+    }
+    if revision_index > 0 {
+        // Everything is attributed; the older revisions have nothing left to contribute
+        report_progress(on_progress, count, count, revision_index - 1)?;
+    }
+
+    let origins = build_origins(&slots, &metadata, last);
+
+    approximated_revisions.reverse();
+    empty_revisions.reverse();
+    Ok(Tracked {
+        origins: origins.into_iter().take(final_len).collect(),
+        revision_count: count,
+        empty_revisions: if options.report_empty_revisions {
+            empty_revisions
+        } else {
+            Vec::new()
+        },
+        report: BlameReport {
+            approximated_revisions,
+            warnings,
+        },
+    })
+}
+
+/// Links the pending units the hunk introduced to the deleted units they replaced, which
+/// become pending in `old_revision` under new slots
+fn link_replaced<'a, U: Unit + ?Sized>(
+    hunk: &Hunk,
+    old_units: &[&'a U],
+    new_units: &[&'a U],
+    old_revision: usize,
+    pairing: &mut Pairing,
+    slots: &mut Vec<Slot<'a, U>>,
+    pending: &mut Vec<(usize, usize)>,
+) {
+    if hunk.introduced.is_empty() {
+        return;
+    }
+
+    // Pairs depend on the whole hunk, not just on the units still tracked
+    let pairs = pairing.pair(old_units, new_units, &hunk.deleted, &hunk.inserted);
+    for (new_index, old_index) in pairs {
+        let Ok(position) = hunk
+            .introduced
+            .binary_search_by_key(&new_index, |&(index, _)| index)
+        else {
+            continue;
+        };
+        let previous_slot = slots.len();
+        slots.push(Slot {
+            content: old_units[old_index],
+            origin: None,
+            previous: None,
+        });
+        slots[hunk.introduced[position].1].previous =
+            Some((old_revision, old_index, previous_slot));
+        pending.push((old_index, previous_slot));
+    }
+}
+
+/// Origins of every slot, given the metadata of the revisions reached, newest first
+fn build_origins<'a, T, U: ?Sized>(
+    slots: &[Slot<'a, U>],
+    metadata: &[Rc<T>],
+    last: usize,
+) -> Vec<Origin<'a, T, U>> {
+    let metadata_of = |revision_index: usize| Rc::clone(&metadata[last - revision_index]);
+
+    // A unit's previous slot is always opened after it, so building from the last slot back
+    // finds every previous origin already built
+    let mut origins: Vec<Origin<'a, T, U>> = Vec::with_capacity(slots.len());
+    for slot in slots.iter().rev() {
+        let (revision_index, line_number) = slot
+            .origin
+            .expect("every tracked unit is attributed by the end of the walk");
+        let previous = slot
+            .previous
+            .map(|(revision_index, line_number, previous_slot)| {
+                Rc::new(Previous {
+                    revision_index,
+                    line_number,
+                    metadata: metadata_of(revision_index),
+                    origin: origins[slots.len() - 1 - previous_slot].clone(),
+                })
+            });
+        origins.push(Origin {
+            content: slot.content,
+            revision_index,
+            line_number,
+            metadata: metadata_of(revision_index),
+            previous,
+        });
+    }
+    origins.reverse();
+    origins
+}
+
+/// Checks the whole history oldest first, as forward tracking does along the way
+fn validate<'a, T, U, F>(count: usize, revision: &F) -> Vec<HistoryWarning>
+where
+    U: Unit + ?Sized + 'a,
+    F: Fn(usize) -> (Vec<&'a U>, Rc<T>),
+{
+    let mut validator = HistoryValidator::new();
+    let mut warnings = Vec::new();
+    let mut previous: Option<Vec<&'a U>> = None;
+    for revision_index in 0..count {
+        let (units, metadata) = revision(revision_index);
+        let identical = previous.as_ref().is_some_and(|previous| *previous == units);
+        validator.check(&mut warnings, revision_index, &units, &metadata, identical);
+        previous = Some(units);
+    }
+    warnings
+}
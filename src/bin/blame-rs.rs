@@ -0,0 +1,229 @@
+use blame_rs::{
+    BlameOptions, BlameResult, BlameRevision, DiffAlgorithm, HeatmapFormat, blame_with_options,
+    render_heatmap,
+};
+use clap::{Parser, ValueEnum};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+
+/// Line-by-line authorship tracking for revisioned text files
+#[derive(Debug, Parser)]
+#[command(name = "blame-rs", version)]
+struct Cli {
+    /// Revision files ordered oldest to newest, or a single directory of numbered revisions
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Diff algorithm used between consecutive revisions
+    #[arg(short, long, value_enum, default_value_t = AlgorithmArg::Myers)]
+    algorithm: AlgorithmArg,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = FormatArg::Plain)]
+    format: FormatArg,
+
+    /// Only show lines START through END (1-based, inclusive), e.g. `10,20` or `10,`
+    #[arg(short = 'L', value_name = "START,END", value_parser = parse_line_range)]
+    lines: Option<LineRange>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AlgorithmArg {
+    Myers,
+    Patience,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FormatArg {
+    /// Revision, line number and content per line
+    Plain,
+    /// Age heatmap with ANSI colors
+    Ansi,
+    /// Age heatmap as an HTML table
+    Html,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LineRange {
+    start: usize,
+    end: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct RevisionFile {
+    name: String,
+}
+
+fn parse_line_range(value: &str) -> Result<LineRange, String> {
+    let (start, end) = value.split_once(',').unwrap_or((value, ""));
+
+    let start = start
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("invalid start line `{}`", start))?;
+    if start == 0 {
+        return Err("line numbers start at 1".to_string());
+    }
+
+    let end = match end.trim() {
+        "" => None,
+        end => {
+            let end = end
+                .parse::<usize>()
+                .map_err(|_| format!("invalid end line `{}`", end))?;
+            if end < start {
+                return Err(format!("end line {} is before start line {}", end, start));
+            }
+            Some(end)
+        }
+    };
+
+    Ok(LineRange { start, end })
+}
+
+// Trailing digits of the file stem (rev2.txt -> 2), so rev2 sorts before rev10
+fn parse_revision_index(path: &Path) -> Option<usize> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    stem[stem.len() - digits..].parse::<usize>().ok()
+}
+
+fn collect_revision_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    if let [dir] = paths
+        && dir.is_dir()
+    {
+        let entries = fs::read_dir(dir)
+            .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?;
+
+        // Only numbered files are revisions; anything else in the directory is ignored
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?
+                .path();
+            if let Some(index) = parse_revision_index(&path)
+                && path.is_file()
+            {
+                files.push((index, path));
+            }
+        }
+
+        if files.is_empty() {
+            return Err(format!(
+                "no numbered revision files found in {}",
+                dir.display()
+            ));
+        }
+
+        files.sort();
+        return Ok(files.into_iter().map(|(_, path)| path).collect());
+    }
+
+    Ok(paths.to_vec())
+}
+
+fn select_lines<'a>(
+    result: BlameResult<'a, RevisionFile>,
+    range: Option<LineRange>,
+) -> Result<BlameResult<'a, RevisionFile>, String> {
+    let Some(range) = range else {
+        return Ok(result);
+    };
+
+    if range.start > result.len() {
+        return Err(format!(
+            "line range starts at {} but the file has only {} lines",
+            range.start,
+            result.len()
+        ));
+    }
+
+    let end = range.end.unwrap_or(usize::MAX).min(result.len());
+    Ok(BlameResult::new(
+        result.lines()[range.start - 1..end].to_vec(),
+    ))
+}
+
+fn render_plain(result: &BlameResult<'_, RevisionFile>) -> String {
+    let name_width = result
+        .iter()
+        .map(|line| line.revision_metadata.name.len())
+        .max()
+        .unwrap_or(0);
+    let line_width = result
+        .iter()
+        .map(|line| line.line_number + 1)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+
+    let mut out = String::new();
+    for line in result.iter() {
+        out.push_str(&format!(
+            "{:<name_width$} {:>line_width$}) {}\n",
+            line.revision_metadata.name,
+            line.line_number + 1,
+            line.content.trim_end_matches(['\r', '\n'])
+        ));
+    }
+    out
+}
+
+fn run(cli: Cli) -> Result<String, String> {
+    let files = collect_revision_files(&cli.paths)?;
+
+    let contents: Vec<String> = files
+        .iter()
+        .map(|path| {
+            fs::read_to_string(path)
+                .map_err(|err| format!("failed to read {}: {}", path.display(), err))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let revisions: Vec<BlameRevision<RevisionFile>> = files
+        .iter()
+        .zip(&contents)
+        .map(|(path, content)| BlameRevision {
+            content,
+            metadata: Rc::new(RevisionFile {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string()),
+            }),
+        })
+        .collect();
+
+    let options = BlameOptions {
+        algorithm: match cli.algorithm {
+            AlgorithmArg::Myers => DiffAlgorithm::Myers,
+            AlgorithmArg::Patience => DiffAlgorithm::Patience,
+        },
+        ..Default::default()
+    };
+
+    let result = blame_with_options(&revisions, options).map_err(|err| err.to_string())?;
+    let result = select_lines(result, cli.lines)?;
+
+    Ok(match cli.format {
+        FormatArg::Plain => render_plain(&result),
+        FormatArg::Ansi => render_heatmap(&result, HeatmapFormat::Ansi),
+        FormatArg::Html => render_heatmap(&result, HeatmapFormat::Html),
+    })
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,26 +1,205 @@
+use crate::backward::track_backward;
+use crate::tokens::{LineTokenizer, Tokenizer};
 use crate::types::{
-    BlameError, BlameLine, BlameOptions, BlameResult, BlameRevision, DiffAlgorithm,
+    BlameError, BlameLimits, BlameLine, BlameOptions, BlameReport, BlameResult, BlameStrategy,
+    ByteBlameResult, ByteRevision, DeadlineFallback, DiffAlgorithm, DiffInvariant, HistoryWarning,
+    OwnedBlameResult, OwnedLine, OwnedPrevious, PreviousLine, Progress, Revision,
 };
-use similar::{Algorithm, ChangeTag, capture_diff_slices};
+use similar::algorithms::{Capture, diff_slices_deadline};
+use similar::{Algorithm, DiffOp, DiffTag, TextDiff};
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+use std::ops::{ControlFlow, Range};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+/// How far into a revision to look for NUL bytes when detecting binary content (as git does)
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Most revisions, and most units per revision, that origin spans can index with `u32`
+const MAX_TRACKED: usize = u32::MAX as usize;
+
+/// How many deleted units, after the last paired one, an inserted unit is compared with
+const PAIRING_WINDOW: usize = 16;
+
+/// Content a tracked unit can hold: text (`str`) or raw bytes (`[u8]`)
+pub(crate) trait Unit: Hash + Eq {
+    fn as_bytes(&self) -> &[u8];
+
+    /// Similarity to `other` in percent, ignoring line terminators
+    fn similarity(&self, other: &Self) -> f32;
+}
+
+impl Unit for str {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn similarity(&self, other: &Self) -> f32 {
+        let old = self.trim_end_matches(['\r', '\n']);
+        let new = other.trim_end_matches(['\r', '\n']);
+        TextDiff::from_chars(old, new).ratio() * 100.0
+    }
+}
+
+impl Unit for [u8] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn similarity(&self, other: &Self) -> f32 {
+        let old = trim_line_terminator(self);
+        let new = trim_line_terminator(other);
+        if old.is_empty() && new.is_empty() {
+            return 100.0;
+        }
+
+        let matched: usize = capture_diff_ops(Algorithm::Myers, old, new, None)
+            .iter()
+            .map(|op| match *op {
+                DiffOp::Equal { len, .. } => len,
+                _ => 0,
+            })
+            .sum();
+        200.0 * matched as f32 / (old.len() + new.len()) as f32
+    }
+}
+
+fn trim_line_terminator(mut bytes: &[u8]) -> &[u8] {
+    while let [rest @ .., b'\r' | b'\n'] = bytes {
+        bytes = rest;
+    }
+    bytes
+}
+
+/// Where a tracked unit (a line, or a token in finer-grained modes) came from
 #[derive(Debug)]
-struct LineOrigin<'a, T> {
-    content: &'a str,
-    metadata: Rc<T>,
+pub(crate) struct Origin<'a, T, U: ?Sized = str> {
+    pub(crate) content: &'a U,
+    pub(crate) revision_index: usize,
+    /// Index of the unit within the revision that introduced it
+    pub(crate) line_number: usize,
+    pub(crate) metadata: Rc<T>,
+    /// The unit this one replaced, if it was paired with one by similarity
+    pub(crate) previous: Option<Rc<Previous<'a, T, U>>>,
 }
 
-impl<'a, T> Clone for LineOrigin<'a, T> {
+impl<T, U: ?Sized> Clone for Origin<'_, T, U> {
     fn clone(&self) -> Self {
         Self {
             content: self.content,
+            revision_index: self.revision_index,
+            line_number: self.line_number,
             metadata: Rc::clone(&self.metadata),
+            previous: self.previous.clone(),
         }
     }
 }
 
-fn iter_lines_preserve_terminator(content: &str) -> impl Iterator<Item = &str> {
-    content.split_inclusive('\n')
+/// A replaced unit as it was in the revision just before the edit
+#[derive(Debug)]
+pub(crate) struct Previous<'a, T, U: ?Sized = str> {
+    pub(crate) revision_index: usize,
+    pub(crate) line_number: usize,
+    pub(crate) metadata: Rc<T>,
+    /// Origin of the replaced unit, which links further back through its own `previous`
+    pub(crate) origin: Origin<'a, T, U>,
+}
+
+impl<'a, T, U: ?Sized> Previous<'a, T, U> {
+    fn to_previous_line(&self) -> PreviousLine<'a, T, U> {
+        PreviousLine {
+            revision_index: self.revision_index,
+            line_number: self.line_number,
+            content: self.origin.content,
+            revision_metadata: Rc::clone(&self.metadata),
+        }
+    }
+}
+
+// `capture_diff_slices` compacts hunks, which in similar 3.0.0 can emit overlapping,
+// out-of-order ops for inputs with many repeated lines. Plain capture keeps ops exact.
+fn capture_diff_ops<U: Hash + Eq>(
+    algorithm: Algorithm,
+    old: &[U],
+    new: &[U],
+    deadline: Option<Instant>,
+) -> Vec<DiffOp> {
+    let mut capture = Capture::new();
+    let Ok(()) = diff_slices_deadline(algorithm, &mut capture, old, new, deadline);
+    capture.into_ops()
+}
+
+/// Diffs the interned ids of two edited middles, reporting the common `prefix` and `suffix`
+/// around them as equal so the ops cover both whole sequences
+fn trimmed_diff_ops(
+    algorithm: Algorithm,
+    old_middle: &[usize],
+    new_middle: &[usize],
+    prefix: usize,
+    suffix: usize,
+    deadline: Option<Instant>,
+) -> Vec<DiffOp> {
+    let (old_end, new_end) = (prefix + old_middle.len(), prefix + new_middle.len());
+    let middle = capture_diff_ops(algorithm, old_middle, new_middle, deadline);
+
+    let mut ops = Vec::with_capacity(middle.len() + 2);
+    if prefix > 0 {
+        ops.push(DiffOp::Equal {
+            old_index: 0,
+            new_index: 0,
+            len: prefix,
+        });
+    }
+    ops.extend(middle.into_iter().map(|op| match op {
+        DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } => DiffOp::Equal {
+            old_index: old_index + prefix,
+            new_index: new_index + prefix,
+            len,
+        },
+        DiffOp::Delete {
+            old_index,
+            old_len,
+            new_index,
+        } => DiffOp::Delete {
+            old_index: old_index + prefix,
+            old_len,
+            new_index: new_index + prefix,
+        },
+        DiffOp::Insert {
+            old_index,
+            new_index,
+            new_len,
+        } => DiffOp::Insert {
+            old_index: old_index + prefix,
+            new_index: new_index + prefix,
+            new_len,
+        },
+        DiffOp::Replace {
+            old_index,
+            old_len,
+            new_index,
+            new_len,
+        } => DiffOp::Replace {
+            old_index: old_index + prefix,
+            old_len,
+            new_index: new_index + prefix,
+            new_len,
+        },
+    }));
+    if suffix > 0 {
+        ops.push(DiffOp::Equal {
+            old_index: old_end,
+            new_index: new_end,
+            len: suffix,
+        });
+    }
+    ops
 }
 
 /// Performs a blame operation on a sequence of revisions to determine the origin of each line.
@@ -40,7 +219,7 @@ fn iter_lines_preserve_terminator(content: &str) -> impl Iterator<Item = &str> {
 /// # Errors
 ///
 /// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
-/// Returns `BlameError::InvalidInput` if diff invariants are violated.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
 ///
 /// # Example
 ///
@@ -67,14 +246,19 @@ fn iter_lines_preserve_terminator(content: &str) -> impl Iterator<Item = &str> {
 ///
 /// let result = blame(&revisions)?;
 /// ```
-pub fn blame<'a, T>(
-    revisions: &'a [BlameRevision<'a, T>],
-) -> Result<BlameResult<'a, T>, BlameError> {
+pub fn blame<'a, T, R>(revisions: &'a [R]) -> Result<BlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
     blame_with_options(revisions, BlameOptions::default())
 }
 
 /// Performs a blame operation with custom options.
 ///
+/// Like every slice-based function, this accepts any [`Revision`]: borrowed
+/// [`BlameRevision`](crate::BlameRevision)s or [`OwnedRevision`](crate::OwnedRevision)s
+/// that own their content.
+///
 /// # Arguments
 ///
 /// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
@@ -88,7 +272,7 @@ pub fn blame<'a, T>(
 /// # Errors
 ///
 /// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
-/// Returns `BlameError::InvalidInput` if diff invariants are violated.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
 ///
 /// # Example
 ///
@@ -97,93 +281,1270 @@ pub fn blame<'a, T>(
 ///
 /// let options = BlameOptions {
 ///     algorithm: DiffAlgorithm::Patience,
+///     ..Default::default()
 /// };
 ///
 /// let result = blame_with_options(&revisions, options)?;
 /// ```
-pub fn blame_with_options<'a, T>(
-    revisions: &'a [BlameRevision<'a, T>],
+pub fn blame_with_options<'a, T, R>(
+    revisions: &'a [R],
     options: BlameOptions,
-) -> Result<BlameResult<'a, T>, BlameError> {
+) -> Result<BlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    blame_with_tokenizer(revisions, options, &LineTokenizer)
+}
+
+/// Performs a blame operation using a custom unit of tracking.
+///
+/// Each revision is split by `tokenizer` and every token is tracked forward through the
+/// revisions instead of every line. Each `BlameLine` in the result is one token of the
+/// final revision, and `line_number` is the token's index.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+/// * `tokenizer` - Splits each revision's content into the units to track
+///
+/// # Returns
+///
+/// Returns a `BlameResult` containing each token of the final revision along with metadata
+/// about which revision introduced that token.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_with_tokenizer, BlameOptions, WordTokenizer};
+///
+/// let result = blame_with_tokenizer(&revisions, BlameOptions::default(), &WordTokenizer)?;
+/// ```
+pub fn blame_with_tokenizer<'a, T, K, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+    tokenizer: &K,
+) -> Result<BlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+    K: Tokenizer + ?Sized,
+{
     if revisions.is_empty() {
         return Err(BlameError::EmptyRevisions);
     }
 
-    let similar_algorithm = match options.algorithm {
-        DiffAlgorithm::Myers => Algorithm::Myers,
-        DiffAlgorithm::Patience => Algorithm::Patience,
-    };
+    let tracked = track_origins(
+        revisions,
+        options,
+        |content| tokenizer.tokenize(content),
+        &mut |_| ControlFlow::Continue(()),
+    )?;
 
-    let revision_lines: Vec<Vec<&'a str>> = revisions
-        .iter()
-        .map(|revision| iter_lines_preserve_terminator(revision.content).collect())
+    Ok(tracked_result(tracked))
+}
+
+/// Performs a blame operation with progress reporting and cancellation.
+///
+/// `on_progress` is called after each revision is processed with the number of revisions
+/// done so far and the total, which is enough to drive a progress bar. Returning
+/// `ControlFlow::Break(())` stops the blame before the next revision is diffed; a break
+/// after the last revision has nothing left to cancel and is ignored.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+/// * `on_progress` - Called between revision steps; decides whether to keep going
+///
+/// # Returns
+///
+/// Returns a `BlameResult` containing each line of the final revision along with metadata
+/// about which revision introduced that line.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::Cancelled` if `on_progress` asked to stop.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_with_progress, BlameOptions};
+/// use std::ops::ControlFlow;
+/// use std::sync::atomic::Ordering;
+///
+/// let result = blame_with_progress(&revisions, BlameOptions::default(), |progress| {
+///     progress_bar.set(progress.fraction());
+///     if cancel_requested.load(Ordering::Relaxed) {
+///         ControlFlow::Break(())
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// });
+/// ```
+pub fn blame_with_progress<'a, T, R, F>(
+    revisions: &'a [R],
+    options: BlameOptions,
+    mut on_progress: F,
+) -> Result<BlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+    F: FnMut(Progress) -> ControlFlow<()>,
+{
+    if revisions.is_empty() {
+        return Err(BlameError::EmptyRevisions);
+    }
+
+    let tracked = track_origins(
+        revisions,
+        options,
+        |content| LineTokenizer.tokenize(content),
+        &mut on_progress,
+    )?;
+
+    Ok(tracked_result(tracked))
+}
+
+/// Performs a blame operation over revisions produced one at a time by an iterator.
+///
+/// Unlike [`blame_with_options`], the history does not have to be collected into a slice
+/// first: each revision is dropped once the next one is diffed against it, so only two
+/// revisions are alive at a time. Revisions can own their content, as
+/// [`OwnedRevision`](crate::OwnedRevision)s read from storage do.
+///
+/// # Arguments
+///
+/// * `revisions` - Revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns an `OwnedBlameResult` holding the last revision and a copy of every line a
+/// reported line replaced; [`OwnedBlameResult::result`] borrows it as a `BlameResult`.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the iterator yields no revisions.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_iter, BlameOptions, OwnedRevision};
+/// use std::rc::Rc;
+///
+/// let blamed = blame_iter(
+///     commits.iter().map(|commit| OwnedRevision {
+///         // Read on demand; each revision's text is freed once the next one is diffed
+///         content: store.load(&commit.blob_id),
+///         metadata: Rc::clone(&commit.info),
+///     }),
+///     BlameOptions::default(),
+/// )?;
+/// for line in blamed.result().iter() {
+///     println!("{}: {}", line.revision_metadata.author, line.content);
+/// }
+/// ```
+pub fn blame_iter<R, I>(
+    revisions: I,
+    options: BlameOptions,
+) -> Result<OwnedBlameResult<R>, BlameError>
+where
+    R: Revision,
+    I: IntoIterator<Item = R>,
+{
+    let mut revisions = revisions.into_iter();
+    let mut current = revisions.next().ok_or(BlameError::EmptyRevisions)?;
+    // Each revision is split once; its lines are kept as byte ranges so they outlive the
+    // borrow of its content and serve as the old side of the next step
+    let mut lines = line_ranges(current.content());
+
+    let mut state = TrackerState::new(
+        &lines_at(current.content(), &lines),
+        Rc::clone(current.metadata()),
+        options,
+    )?;
+    for revision in revisions {
+        let new_lines = line_ranges(revision.content());
+        let old_units = lines_at(current.content(), &lines);
+        state.advance(
+            &old_units,
+            &lines_at(revision.content(), &new_lines),
+            Rc::clone(revision.metadata()),
+            |replaced| OwnedPrevious {
+                revision_index: replaced.revision_index,
+                line_number: replaced.line_number,
+                content: old_units[replaced.line_number].into(),
+                revision_metadata: replaced.metadata,
+            },
+        )?;
+        // Dropped before the iterator produces the next revision
+        current = revision;
+        lines = new_lines;
+    }
+
+    let lines = state
+        .origins()
+        .into_iter()
+        .zip(lines)
+        .map(|(origin, range)| OwnedLine {
+            range,
+            revision_index: origin.revision_index,
+            revision_metadata: origin.metadata,
+            previous: origin.previous,
+        })
         .collect();
 
-    let mut line_origins: Vec<LineOrigin<'a, T>> = Vec::with_capacity(revision_lines[0].len());
-    let first_metadata = Rc::clone(&revisions[0].metadata);
+    Ok(
+        OwnedBlameResult::new(current, lines, state.revision_index + 1)
+            .with_report(state.report)
+            .with_empty_revisions(state.empty_revisions.unwrap_or_default()),
+    )
+}
 
-    for &line in &revision_lines[0] {
-        line_origins.push(LineOrigin {
-            content: line,
-            metadata: Rc::clone(&first_metadata),
-        });
+/// Performs a blame operation on raw bytes, for content that is not valid UTF-8.
+///
+/// Lines are split on `\n` at the byte level and keep their terminators, exactly like the
+/// string version; no decoding is attempted, so Latin-1 or otherwise invalid UTF-8 content
+/// is blamed as-is.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns a `ByteBlameResult` containing each line of the final revision as a byte slice,
+/// along with metadata about which revision introduced that line.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_bytes, BlameOptions, ByteRevision};
+///
+/// let result = blame_bytes(&revisions, BlameOptions::default())?;
+/// for line in result.lines() {
+///     println!("{}: {}", line.revision_index, String::from_utf8_lossy(line.content));
+/// }
+/// ```
+pub fn blame_bytes<'a, T>(
+    revisions: &'a [ByteRevision<'a, T>],
+    options: BlameOptions,
+) -> Result<ByteBlameResult<'a, T>, BlameError> {
+    let Some(first) = revisions.first() else {
+        return Err(BlameError::EmptyRevisions);
+    };
+    if options.strategy == BlameStrategy::Backward {
+        let revision = |idx: usize| {
+            let revision = &revisions[idx];
+            (
+                split_byte_lines(revision.content),
+                Rc::clone(&revision.metadata),
+            )
+        };
+        let tracked = track_backward(revisions.len(), revision, options, &mut |_| {
+            ControlFlow::Continue(())
+        })?;
+        return Ok(tracked_result(tracked));
     }
+    check_revision_count(revisions.len(), &options.limits)?;
 
-    // Forward iteration: track each line's origin through revisions
-    for i in 0..revisions.len() - 1 {
-        let old_lines = &revision_lines[i];
-        let new_lines = &revision_lines[i + 1];
+    let mut tracker = OriginTracker::new(
+        split_byte_lines(first.content),
+        Rc::clone(&first.metadata),
+        options,
+    )?;
+    for revision in &revisions[1..] {
+        tracker.advance(
+            split_byte_lines(revision.content),
+            Rc::clone(&revision.metadata),
+        )?;
+    }
 
-        // Create shared reference to this revision's metadata
-        let shared_metadata = Rc::clone(&revisions[i + 1].metadata);
+    let tracked = tracker.finish();
+    Ok(tracked_result(tracked))
+}
 
-        let diff_ops = capture_diff_slices(similar_algorithm, &old_lines, &new_lines);
+/// Byte ranges of the lines `LineTokenizer` splits `content` into
+fn line_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut start = 0;
+    LineTokenizer
+        .tokenize(content)
+        .into_iter()
+        .map(|line| {
+            let range = start..start + line.len();
+            start = range.end;
+            range
+        })
+        .collect()
+}
 
-        let mut new_line_origins: Vec<LineOrigin<'a, T>> = Vec::with_capacity(new_lines.len());
+fn lines_at<'c>(content: &'c str, ranges: &[Range<usize>]) -> Vec<&'c str> {
+    ranges.iter().map(|range| &content[range.clone()]).collect()
+}
 
-        for op in &diff_ops {
-            for change in op.iter_changes(old_lines, new_lines) {
-                match change.tag() {
-                    ChangeTag::Equal => {
-                        let old_line_num = change.old_index().ok_or_else(|| {
-                            BlameError::InvalidInput(format!(
-                                "diff invariant violated: Equal change had no old index at revision {}",
-                                i + 1
-                            ))
-                        })?;
-                        let origin = line_origins.get(old_line_num).ok_or_else(|| {
-                            BlameError::InvalidInput(format!(
-                                "diff invariant violated: old index {} out of bounds (len {}) at revision {}",
-                                old_line_num,
-                                line_origins.len(),
-                                i + 1
-                            ))
-                        })?;
-                        new_line_origins.push(origin.clone());
-                    }
-                    ChangeTag::Insert => {
-                        new_line_origins.push(LineOrigin {
-                            content: change.value(),
-                            metadata: Rc::clone(&shared_metadata),
-                        });
-                    }
-                    ChangeTag::Delete => {}
-                }
-            }
-        }
+fn split_byte_lines(content: &[u8]) -> Vec<&[u8]> {
+    content.split_inclusive(|&byte| byte == b'\n').collect()
+}
 
-        line_origins = new_line_origins;
-    }
+/// Converts the outcome of forward tracking into a `BlameResult` with everything reported
+/// along the way
+pub(crate) fn tracked_result<'a, T, U: ?Sized>(
+    tracked: Tracked<'a, T, U>,
+) -> BlameResult<'a, T, U> {
+    to_blame_result(tracked.origins)
+        .with_revision_count(tracked.revision_count)
+        .with_report(tracked.report)
+        .with_empty_revisions(tracked.empty_revisions)
+}
 
-    let blame_lines: Vec<BlameLine<'a, T>> = line_origins
+/// Converts origins of the final units into a `BlameResult`, numbering units in order
+pub(crate) fn to_blame_result<'a, T, U: ?Sized>(
+    origins: impl IntoIterator<Item = Origin<'a, T, U>>,
+) -> BlameResult<'a, T, U> {
+    let blame_lines: Vec<BlameLine<'a, T, U>> = origins
         .into_iter()
         .enumerate()
         .map(|(idx, origin)| BlameLine {
             line_number: idx,
             content: origin.content,
+            revision_index: origin.revision_index,
             revision_metadata: origin.metadata,
+            previous: origin.previous.map(|previous| previous.to_previous_line()),
         })
         .collect();
 
-    Ok(BlameResult::new(blame_lines))
+    BlameResult::new(blame_lines)
+}
+
+/// Tracks the origin of every unit through the revisions, diffing forward unless
+/// `BlameStrategy::Backward` is selected.
+///
+/// Each revision is split into units (lines or tokens) by `tokenize` as it is reached, so
+/// only two revisions' units are alive at a time. Returns one origin per unit of the final
+/// revision. `on_progress` is called after each revision and may cancel the remaining steps.
+pub(crate) fn track_origins<'a, T, F, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+    tokenize: F,
+    on_progress: &mut dyn FnMut(Progress) -> ControlFlow<()>,
+) -> Result<Tracked<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+    F: Fn(&'a str) -> Vec<&'a str>,
+{
+    if options.strategy == BlameStrategy::Backward {
+        let revision = |idx: usize| {
+            let revision = &revisions[idx];
+            (tokenize(revision.content()), Rc::clone(revision.metadata()))
+        };
+        return track_backward(revisions.len(), revision, options, on_progress);
+    }
+    check_revision_count(revisions.len(), &options.limits)?;
+
+    let mut tracker = OriginTracker::new(
+        tokenize(revisions[0].content()),
+        Rc::clone(revisions[0].metadata()),
+        options,
+    )?;
+    report_progress(on_progress, 1, revisions.len(), 1)?;
+
+    for (idx, revision) in revisions.iter().enumerate().skip(1) {
+        tracker.advance(tokenize(revision.content()), Rc::clone(revision.metadata()))?;
+        report_progress(on_progress, idx + 1, revisions.len(), idx + 1)?;
+    }
+
+    Ok(tracker.finish())
+}
+
+/// Reports progress, failing with `Cancelled` before `next_revision` if the callback asks to
+/// stop early.
+///
+/// A request to stop once every revision is processed has nothing left to cancel.
+pub(crate) fn report_progress(
+    on_progress: &mut dyn FnMut(Progress) -> ControlFlow<()>,
+    completed: usize,
+    total: usize,
+    next_revision: usize,
+) -> Result<(), BlameError> {
+    match on_progress(Progress { completed, total }) {
+        ControlFlow::Break(()) if completed < total => Err(BlameError::Cancelled {
+            revision_index: next_revision,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails with `TooManyRevisions` if `count` revisions exceed the configured limit
+pub(crate) fn check_revision_count(count: usize, limits: &BlameLimits) -> Result<(), BlameError> {
+    let limit = limits
+        .max_revisions
+        .map_or(MAX_TRACKED, |limit| limit.min(MAX_TRACKED));
+    if count > limit {
+        return Err(BlameError::TooManyRevisions {
+            revision_index: limit,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// Interned ids of the edited middles of two revisions sharing their first `prefix` and last
+/// `suffix` units.
+///
+/// Ids are numbered afresh for every step: they are only compared between the two revisions
+/// being diffed, so nothing outlives the step.
+fn middle_ids<'u, U: Unit + ?Sized>(
+    old_units: &[&'u U],
+    new_units: &[&'u U],
+    prefix: usize,
+    suffix: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut interner = HashMap::new();
+    let old_ids = intern(&mut interner, &old_units[prefix..old_units.len() - suffix]);
+    let new_ids = intern(&mut interner, &new_units[prefix..new_units.len() - suffix]);
+    (old_ids, new_ids)
+}
+
+/// Ids of `units`, shared by equal units
+fn intern<'u, U: Unit + ?Sized>(
+    interner: &mut HashMap<&'u U, usize>,
+    units: &[&'u U],
+) -> Vec<usize> {
+    units
+        .iter()
+        .map(|&unit| {
+            let next = interner.len();
+            *interner.entry(unit).or_insert(next)
+        })
+        .collect()
+}
+
+/// Checks one revision's units against the size and binary-content limits
+pub(crate) fn check_units<U: Unit + ?Sized>(
+    revision_index: usize,
+    units: &[&U],
+    limits: &BlameLimits,
+) -> Result<(), BlameError> {
+    let limit = limits
+        .max_lines
+        .map_or(MAX_TRACKED, |limit| limit.min(MAX_TRACKED));
+    if units.len() > limit {
+        return Err(BlameError::TooManyLines {
+            revision_index,
+            lines: units.len(),
+            limit,
+        });
+    }
+
+    if let Some(limit) = limits.max_line_length
+        && let Some((line_number, unit)) = units
+            .iter()
+            .enumerate()
+            .find(|(_, unit)| unit.as_bytes().len() > limit)
+    {
+        return Err(BlameError::LineTooLong {
+            revision_index,
+            line_number,
+            length: unit.as_bytes().len(),
+            limit,
+        });
+    }
+
+    if limits.reject_binary {
+        let mut remaining = BINARY_SNIFF_LEN;
+        for unit in units {
+            let bytes = unit.as_bytes();
+            let sniffed = &bytes[..bytes.len().min(remaining)];
+            if sniffed.contains(&0) {
+                return Err(BlameError::BinaryContent { revision_index });
+            }
+            remaining -= sniffed.len();
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lengths of the common prefix and suffix of `old` and `new`, which never overlap
+fn common_ends<U: Unit + ?Sized>(old: &[&U], new: &[&U]) -> (usize, usize) {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    (prefix, suffix)
+}
+
+/// Full replacement of `old_len` units by `new_len` units, used when a diff is abandoned
+fn replace_ops(old_len: usize, new_len: usize) -> Vec<DiffOp> {
+    let mut ops = Vec::with_capacity(2);
+    if old_len > 0 {
+        ops.push(DiffOp::Delete {
+            old_index: 0,
+            old_len,
+            new_index: 0,
+        });
+    }
+    if new_len > 0 {
+        ops.push(DiffOp::Insert {
+            old_index: old_len,
+            new_index: 0,
+            new_len,
+        });
+    }
+    ops
+}
+
+/// The diff of one revision step, see [`StepDiffer::step`]
+pub(crate) struct Step {
+    pub(crate) ops: Vec<DiffOp>,
+    /// Whether the ops are an approximation because the deadline hit
+    pub(crate) approximated: bool,
+    pub(crate) pairing: Option<Pairing>,
+}
+
+/// Diffs the interned ids of consecutive revisions within the configured deadlines
+pub(crate) struct StepDiffer {
+    algorithm: Algorithm,
+    limits: BlameLimits,
+    started: Instant,
+}
+
+impl StepDiffer {
+    /// Starts the clock for `BlameLimits::total_deadline`
+    pub(crate) fn new(options: &BlameOptions) -> Self {
+        let algorithm = match options.algorithm {
+            DiffAlgorithm::Myers => Algorithm::Myers,
+            DiffAlgorithm::Patience => Algorithm::Patience,
+        };
+        Self {
+            algorithm,
+            limits: options.limits,
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn limits(&self) -> &BlameLimits {
+        &self.limits
+    }
+
+    /// How much of the common `prefix` and `suffix` to leave out of the diff.
+    ///
+    /// Myers aligns the same way with or without them. Patience anchors on units unique to
+    /// each whole side, so it diffs the full revisions to keep its results unchanged.
+    fn trimmed_ends(&self, prefix: usize, suffix: usize) -> (usize, usize) {
+        match self.algorithm {
+            Algorithm::Patience => (0, 0),
+            _ => (prefix, suffix),
+        }
+    }
+
+    /// Deadline of a step starting now, the sooner of the per-step and total deadlines, with
+    /// the configured duration it comes from
+    fn step_deadline(&self) -> Option<(Instant, Duration)> {
+        let step = self
+            .limits
+            .diff_deadline
+            .map(|duration| (Instant::now() + duration, duration));
+        let total = self
+            .limits
+            .total_deadline
+            .map(|duration| (self.started + duration, duration));
+        match (step, total) {
+            (Some(step), Some(total)) => Some(if step.0 <= total.0 { step } else { total }),
+            (step, total) => step.or(total),
+        }
+    }
+
+    /// Fails with `DeadlineExceeded` for revision `revision_index` when no fallback is
+    /// configured; otherwise the step goes on approximated
+    fn check_fallback(&self, revision_index: usize, deadline: Duration) -> Result<(), BlameError> {
+        match self.limits.deadline_fallback {
+            DeadlineFallback::Error => Err(BlameError::DeadlineExceeded {
+                revision_index,
+                deadline,
+            }),
+            DeadlineFallback::Coarse | DeadlineFallback::Replace => Ok(()),
+        }
+    }
+
+    /// Diffs `new_units`, revision `revision_index`'s, against `old_units` within the step's
+    /// deadline, or returns `None` when the two are identical.
+    ///
+    /// The unchanged ends are found by direct comparison, which spots identical revisions
+    /// without a diff and leaves Myers only the edited middles to hash and diff. Similarity
+    /// pairing is set up for the step when `similarity_threshold` is set and the diff is exact:
+    /// pairing a coarse or full-replace hunk line by line would cost more than the diff saved.
+    pub(crate) fn step<U: Unit + ?Sized>(
+        &self,
+        revision_index: usize,
+        old_units: &[&U],
+        new_units: &[&U],
+        similarity_threshold: Option<u8>,
+    ) -> Result<Option<Step>, BlameError> {
+        let (prefix, suffix) = common_ends(old_units, new_units);
+        if prefix == old_units.len() && prefix == new_units.len() {
+            return Ok(None);
+        }
+
+        let (prefix, suffix) = self.trimmed_ends(prefix, suffix);
+        let (old_ids, new_ids) = middle_ids(old_units, new_units, prefix, suffix);
+        let deadline = self.step_deadline();
+        let (ops, approximated) =
+            self.diff(revision_index, deadline, &old_ids, &new_ids, prefix, suffix)?;
+        let pairing = similarity_threshold
+            .filter(|_| !approximated)
+            .map(|threshold| Pairing::new(threshold, deadline));
+        Ok(Some(Step {
+            ops,
+            approximated,
+            pairing,
+        }))
+    }
+
+    /// Whether the step's pairing ran past its deadline, which fails with `DeadlineExceeded`
+    /// for revision `revision_index` when no fallback is configured
+    pub(crate) fn finish_pairing(
+        &self,
+        revision_index: usize,
+        pairing: Option<&Pairing>,
+    ) -> Result<bool, BlameError> {
+        match pairing.and_then(Pairing::timed_out) {
+            Some(duration) => {
+                self.check_fallback(revision_index, duration)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Diffs the ids of two edited middles, `new_ids` being revision `revision_index`'s, within
+    /// the step's `deadline`.
+    ///
+    /// Returns the ops and whether they are an approximation because the deadline hit.
+    fn diff(
+        &self,
+        revision_index: usize,
+        deadline: Option<(Instant, Duration)>,
+        old_ids: &[usize],
+        new_ids: &[usize],
+        prefix: usize,
+        suffix: usize,
+    ) -> Result<(Vec<DiffOp>, bool), BlameError> {
+        let diff =
+            |deadline| trimmed_diff_ops(self.algorithm, old_ids, new_ids, prefix, suffix, deadline);
+
+        let Some((instant, duration)) = deadline else {
+            return Ok((diff(None), false));
+        };
+
+        // Once the total budget is spent, skip straight to the fallback
+        let ops = if Instant::now() > instant {
+            None
+        } else {
+            let ops = diff(Some(instant));
+            if Instant::now() <= instant {
+                return Ok((ops, false));
+            }
+            Some(ops)
+        };
+
+        self.check_fallback(revision_index, duration)?;
+        if self.limits.deadline_fallback == DeadlineFallback::Replace {
+            let (old_len, new_len) = (prefix + old_ids.len(), prefix + new_ids.len());
+            return Ok((replace_ops(old_len + suffix, new_len + suffix), true));
+        }
+        // A diff whose deadline already passed stops refining right away
+        let ops = ops.unwrap_or_else(|| diff(Some(instant)));
+        Ok((ops, true))
+    }
+}
+
+/// Outcome of forward tracking: origins of the final units and what was reported on the way
+pub(crate) struct Tracked<'a, T, U: ?Sized = str> {
+    pub(crate) origins: Vec<Origin<'a, T, U>>,
+    pub(crate) revision_count: usize,
+    pub(crate) empty_revisions: Vec<usize>,
+    pub(crate) report: BlameReport,
+}
+
+/// Spots suspicious histories for `BlameOptions::validate`
+pub(crate) struct HistoryValidator<T> {
+    // First revision seen with each metadata `Rc`, and a hash of its units. Holding the `Rc`
+    // keeps the allocation alive, so a later revision can't reuse the address.
+    metadata_owners: HashMap<*const T, (Rc<T>, usize, u64)>,
+}
+
+impl<T> HistoryValidator<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            metadata_owners: HashMap::new(),
+        }
+    }
+
+    /// Records revision `revision_index`, given whether it repeats the previous revision, and
+    /// adds what looks wrong about it to `warnings`
+    pub(crate) fn check<U: Unit + ?Sized>(
+        &mut self,
+        warnings: &mut Vec<HistoryWarning>,
+        revision_index: usize,
+        units: &[&U],
+        metadata: &Rc<T>,
+        identical_to_previous: bool,
+    ) {
+        if identical_to_previous {
+            warnings.push(HistoryWarning::IdenticalToPrevious { revision_index });
+        }
+
+        let mut hasher = DefaultHasher::new();
+        units.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        match self.metadata_owners.entry(Rc::as_ptr(metadata)) {
+            Entry::Occupied(entry) => {
+                let &(_, shared_with, owner_hash) = entry.get();
+                if owner_hash != hash {
+                    warnings.push(HistoryWarning::SharedMetadata {
+                        revision_index,
+                        shared_with,
+                    });
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((Rc::clone(metadata), revision_index, hash));
+            }
+        }
+    }
+}
+
+/// Forward-tracking state: the units of the latest revision and where each came from
+pub(crate) struct OriginTracker<'a, T, U: ?Sized = str> {
+    state: TrackerState<T, Previous<'a, T, U>>,
+    units: Vec<&'a U>,
+}
+
+impl<'a, T, U: Unit + ?Sized> OriginTracker<'a, T, U> {
+    /// Starts tracking at the first revision, attributing all of its units to it
+    pub(crate) fn new(
+        units: Vec<&'a U>,
+        metadata: Rc<T>,
+        options: BlameOptions,
+    ) -> Result<Self, BlameError> {
+        let state = TrackerState::new(&units, metadata, options)?;
+        Ok(Self { state, units })
+    }
+
+    /// Index of the revision the current origins describe
+    pub(crate) fn revision_index(&self) -> usize {
+        self.state.revision_index
+    }
+
+    /// One origin per unit of the current revision
+    pub(crate) fn origins(&self) -> Vec<Origin<'a, T, U>> {
+        self.state
+            .origins()
+            .into_iter()
+            .zip(&self.units)
+            .map(|(origin, &content)| origin.with_content(content))
+            .collect()
+    }
+
+    /// What was reported so far
+    pub(crate) fn report(&self) -> BlameReport {
+        self.state.report.clone()
+    }
+
+    /// Revisions so far that added no units, empty unless reporting is enabled
+    pub(crate) fn empty_revisions(&self) -> &[usize] {
+        self.state.empty_revisions()
+    }
+
+    pub(crate) fn finish(self) -> Tracked<'a, T, U> {
+        let origins = self.origins();
+        let state = self.state;
+        Tracked {
+            origins,
+            revision_count: state.revision_index + 1,
+            empty_revisions: state.empty_revisions.unwrap_or_default(),
+            report: state.report,
+        }
+    }
+
+    /// Moves to the next revision, diffing its units against the current ones
+    pub(crate) fn advance(
+        &mut self,
+        new_units: Vec<&'a U>,
+        new_metadata: Rc<T>,
+    ) -> Result<(), BlameError> {
+        let units = &self.units;
+        self.state
+            .advance(units, &new_units, new_metadata, |replaced| Previous {
+                revision_index: replaced.revision_index,
+                line_number: replaced.line_number,
+                metadata: replaced.metadata,
+                origin: replaced.origin.with_content(units[replaced.line_number]),
+            })?;
+        self.units = new_units;
+        Ok(())
+    }
+}
+
+/// Where a tracked unit came from, before its content is attached
+///
+/// `P` is what a `previous` link holds: a [`Previous`] borrowing the replaced unit, or an
+/// `OwnedPrevious` copying it when revisions are dropped as tracking moves on.
+pub(crate) struct SpanOrigin<T, P> {
+    pub(crate) revision_index: usize,
+    pub(crate) line_number: usize,
+    pub(crate) metadata: Rc<T>,
+    pub(crate) previous: Option<Rc<P>>,
+}
+
+impl<'a, T, U: ?Sized> SpanOrigin<T, Previous<'a, T, U>> {
+    fn with_content(self, content: &'a U) -> Origin<'a, T, U> {
+        Origin {
+            content,
+            revision_index: self.revision_index,
+            line_number: self.line_number,
+            metadata: self.metadata,
+            previous: self.previous,
+        }
+    }
+}
+
+/// A unit of the current revision that a unit of the next one replaced
+struct Replaced<T, P> {
+    revision_index: usize,
+    line_number: usize,
+    metadata: Rc<T>,
+    origin: SpanOrigin<T, P>,
+}
+
+/// Origins of the current revision's units, tracked without holding on to the units.
+///
+/// Every step is given the units of the current and next revisions, so callers decide how
+/// long revisions live. `P` is what a `previous` link holds.
+struct TrackerState<T, P> {
+    differ: StepDiffer,
+    similarity_threshold: Option<u8>,
+    report: BlameReport,
+    validator: Option<HistoryValidator<T>>,
+    empty_revisions: Option<Vec<usize>>,
+    revision_index: usize,
+    // Metadata of every revision so far, indexed by revision
+    metadata: Vec<Rc<T>>,
+    // Run-length encoded origins of the current units
+    spans: Vec<OriginSpan<P>>,
+}
+
+/// A run of consecutive units that came from consecutive units of one revision
+///
+/// Unit `k` of the run is unit `line_number + k` of revision `revision_index`. A unit paired
+/// with the unit it replaced always gets a run of its own, carrying the `previous` link.
+struct OriginSpan<P> {
+    revision_index: u32,
+    line_number: u32,
+    len: u32,
+    previous: Option<Rc<P>>,
+}
+
+impl<P> Clone for OriginSpan<P> {
+    fn clone(&self) -> Self {
+        Self {
+            revision_index: self.revision_index,
+            line_number: self.line_number,
+            len: self.len,
+            previous: self.previous.clone(),
+        }
+    }
+}
+
+/// Appends `span`, extending the last span instead when it continues it
+fn push_span<P>(spans: &mut Vec<OriginSpan<P>>, span: OriginSpan<P>) {
+    if let Some(last) = spans.last_mut()
+        && last.previous.is_none()
+        && span.previous.is_none()
+        && last.revision_index == span.revision_index
+        && last.line_number + last.len == span.line_number
+    {
+        last.len += span.len;
+        return;
+    }
+    spans.push(span);
+}
+
+impl<T, P> TrackerState<T, P> {
+    /// Starts tracking at the first revision, attributing all of its units to it
+    fn new<U: Unit + ?Sized>(
+        units: &[&U],
+        metadata: Rc<T>,
+        options: BlameOptions,
+    ) -> Result<Self, BlameError> {
+        check_units(0, units, &options.limits)?;
+
+        let mut report = BlameReport::default();
+        let validator = options.validate.then(|| {
+            let mut validator = HistoryValidator::new();
+            validator.check(&mut report.warnings, 0, units, &metadata, false);
+            validator
+        });
+
+        let mut spans = Vec::new();
+        if !units.is_empty() {
+            spans.push(OriginSpan {
+                revision_index: 0,
+                line_number: 0,
+                len: units.len() as u32,
+                previous: None,
+            });
+        }
+
+        Ok(Self {
+            differ: StepDiffer::new(&options),
+            similarity_threshold: options.similarity_threshold,
+            report,
+            validator,
+            empty_revisions: options.report_empty_revisions.then(|| {
+                if units.is_empty() {
+                    vec![0]
+                } else {
+                    Vec::new()
+                }
+            }),
+            revision_index: 0,
+            metadata: vec![metadata],
+            spans,
+        })
+    }
+
+    /// One origin per unit of the current revision
+    fn origins(&self) -> Vec<SpanOrigin<T, P>> {
+        let mut origins = Vec::new();
+        for span in &self.spans {
+            let revision_index = span.revision_index as usize;
+            let metadata = &self.metadata[revision_index];
+            for line_number in span.line_number..span.line_number + span.len {
+                origins.push(SpanOrigin {
+                    revision_index,
+                    line_number: line_number as usize,
+                    metadata: Rc::clone(metadata),
+                    previous: span.previous.clone(),
+                });
+            }
+        }
+        origins
+    }
+
+    /// Origin of current unit `index`, given the position each span starts at
+    fn origin_at(&self, starts: &[usize], index: usize) -> SpanOrigin<T, P> {
+        let span_index = starts.partition_point(|&start| start <= index) - 1;
+        let span = &self.spans[span_index];
+        let revision_index = span.revision_index as usize;
+        SpanOrigin {
+            revision_index,
+            line_number: span.line_number as usize + (index - starts[span_index]),
+            metadata: Rc::clone(&self.metadata[revision_index]),
+            previous: span.previous.clone(),
+        }
+    }
+
+    fn empty_revisions(&self) -> &[usize] {
+        self.empty_revisions.as_deref().unwrap_or(&[])
+    }
+
+    /// Moves from `old_units`, the current revision, to `new_units`, building the `previous`
+    /// link of every unit paired with the unit it replaced with `link`
+    fn advance<U: Unit + ?Sized>(
+        &mut self,
+        old_units: &[&U],
+        new_units: &[&U],
+        new_metadata: Rc<T>,
+        mut link: impl FnMut(Replaced<T, P>) -> P,
+    ) -> Result<(), BlameError> {
+        let i = self.revision_index;
+        check_revision_count(i + 2, self.differ.limits())?;
+        check_units(i + 1, new_units, self.differ.limits())?;
+
+        let step = self
+            .differ
+            .step(i + 1, old_units, new_units, self.similarity_threshold)?;
+        if let Some(validator) = &mut self.validator {
+            validator.check(
+                &mut self.report.warnings,
+                i + 1,
+                new_units,
+                &new_metadata,
+                step.is_none(),
+            );
+        }
+
+        let Some(Step {
+            ops: diff_ops,
+            mut approximated,
+            mut pairing,
+        }) = step
+        else {
+            // Nothing to diff: every origin carries over unchanged
+            if let Some(empty_revisions) = &mut self.empty_revisions {
+                empty_revisions.push(i + 1);
+            }
+            self.revision_index = i + 1;
+            self.metadata.push(new_metadata);
+            return Ok(());
+        };
+        let old_len = old_units.len();
+
+        // Revision indices and line numbers fit in u32, as checked above
+        let revision = (i + 1) as u32;
+        let mut starts = Vec::with_capacity(self.spans.len());
+        let mut start = 0;
+        for span in &self.spans {
+            starts.push(start);
+            start += span.len as usize;
+        }
+
+        let mut new_spans: Vec<OriginSpan<P>> = Vec::with_capacity(self.spans.len() + 2);
+        // Old indices deleted and new indices inserted since the last unchanged unit
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+
+        for op in &diff_ops {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                if let Some(pairing) = &mut pairing {
+                    let pairs = pairing.pair(old_units, new_units, &deleted, &inserted);
+                    self.link_replaced(&pairs, &mut new_spans, &starts, &mut link);
+                }
+                deleted.clear();
+                inserted.clear();
+
+                if old_range.end > old_len {
+                    return Err(BlameError::DiffInvariantViolated {
+                        revision_index: i + 1,
+                        cause: DiffInvariant::OldIndexOutOfBounds {
+                            old_index: old_range.start.max(old_len),
+                            len: old_len,
+                        },
+                    });
+                }
+
+                // Unchanged runs carry their spans over, cut to the run's ends
+                let mut index = old_range.start;
+                while index < old_range.end {
+                    let span_index = starts.partition_point(|&start| start <= index) - 1;
+                    let span = &self.spans[span_index];
+                    let offset = index - starts[span_index];
+                    let len = (span.len as usize - offset).min(old_range.end - index);
+                    push_span(
+                        &mut new_spans,
+                        OriginSpan {
+                            revision_index: span.revision_index,
+                            line_number: span.line_number + offset as u32,
+                            len: len as u32,
+                            previous: span.previous.clone(),
+                        },
+                    );
+                    index += len;
+                }
+                continue;
+            }
+
+            deleted.extend(old_range);
+            if !new_range.is_empty() {
+                push_span(
+                    &mut new_spans,
+                    OriginSpan {
+                        revision_index: revision,
+                        line_number: new_range.start as u32,
+                        len: new_range.len() as u32,
+                        previous: None,
+                    },
+                );
+                inserted.extend(new_range);
+            }
+        }
+
+        if let Some(pairing) = &mut pairing {
+            let pairs = pairing.pair(old_units, new_units, &deleted, &inserted);
+            self.link_replaced(&pairs, &mut new_spans, &starts, &mut link);
+        }
+        approximated |= self.differ.finish_pairing(i + 1, pairing.as_ref())?;
+
+        if approximated {
+            self.report.approximated_revisions.push(i + 1);
+        }
+        if let Some(empty_revisions) = &mut self.empty_revisions
+            && new_spans.iter().all(|span| span.revision_index != revision)
+        {
+            empty_revisions.push(i + 1);
+        }
+        self.revision_index = i + 1;
+        self.metadata.push(new_metadata);
+        self.spans = new_spans;
+        Ok(())
+    }
+
+    /// Links the units inserted by the hunk just walked to the deleted units they replaced,
+    /// given as `(new index, old index)` pairs.
+    ///
+    /// The hunk's inserted units form the last span of `new_spans`; every linked unit is cut
+    /// out of it into a span of its own.
+    fn link_replaced(
+        &self,
+        pairs: &[(usize, usize)],
+        new_spans: &mut Vec<OriginSpan<P>>,
+        starts: &[usize],
+        link: &mut impl FnMut(Replaced<T, P>) -> P,
+    ) {
+        if pairs.is_empty() {
+            return;
+        }
+        let Some(span) = new_spans.pop() else {
+            return;
+        };
+
+        let old_metadata = &self.metadata[self.revision_index];
+        let mut next = span.line_number;
+        for &(new_index, old_index) in pairs {
+            let new_index = new_index as u32;
+            if new_index > next {
+                new_spans.push(OriginSpan {
+                    len: new_index - next,
+                    line_number: next,
+                    ..span.clone()
+                });
+            }
+            new_spans.push(OriginSpan {
+                revision_index: span.revision_index,
+                line_number: new_index,
+                len: 1,
+                previous: Some(Rc::new(link(Replaced {
+                    revision_index: self.revision_index,
+                    line_number: old_index,
+                    metadata: Rc::clone(old_metadata),
+                    origin: self.origin_at(starts, old_index),
+                }))),
+            });
+            next = new_index + 1;
+        }
+        let end = span.line_number + span.len;
+        if next < end {
+            new_spans.push(OriginSpan {
+                len: end - next,
+                line_number: next,
+                ..span
+            });
+        }
+    }
+}
+
+/// Similarity pairing for one revision step, sharing the step's deadline with its diff
+pub(crate) struct Pairing {
+    threshold: u8,
+    deadline: Option<(Instant, Duration)>,
+    // Set once the deadline passed; later hunks of the step are left unpaired
+    timed_out: bool,
+}
+
+impl Pairing {
+    pub(crate) fn new(threshold: u8, deadline: Option<(Instant, Duration)>) -> Self {
+        Self {
+            threshold,
+            deadline,
+            timed_out: false,
+        }
+    }
+
+    /// Pairs the units deleted and inserted by one hunk, see [`pair_replaced`]
+    pub(crate) fn pair<U: Unit + ?Sized>(
+        &mut self,
+        old_units: &[&U],
+        new_units: &[&U],
+        deleted: &[usize],
+        inserted: &[usize],
+    ) -> Vec<(usize, usize)> {
+        if self.timed_out {
+            return Vec::new();
+        }
+        let deadline = self.deadline.map(|(instant, _)| instant);
+        let (pairs, finished) = pair_replaced(
+            old_units,
+            new_units,
+            deleted,
+            inserted,
+            self.threshold,
+            deadline,
+        );
+        self.timed_out = !finished;
+        pairs
+    }
+
+    /// The deadline's duration, if pairing ran past it
+    pub(crate) fn timed_out(&self) -> Option<Duration> {
+        self.deadline
+            .filter(|_| self.timed_out)
+            .map(|(_, duration)| duration)
+    }
+}
+
+/// Pairs the units deleted and inserted by one hunk, returning `(new_index, old_index)` for
+/// each inserted unit and the deleted unit it replaced, and whether pairing finished before
+/// `deadline`.
+///
+/// Pairs keep their relative order: each inserted unit takes the nearest deleted unit after
+/// the previously paired one that reaches `threshold` percent. Only the next
+/// `PAIRING_WINDOW` deleted units are compared, so the cost grows linearly with the hunk.
+fn pair_replaced<U: Unit + ?Sized>(
+    old_units: &[&U],
+    new_units: &[&U],
+    deleted: &[usize],
+    inserted: &[usize],
+    threshold: u8,
+    deadline: Option<Instant>,
+) -> (Vec<(usize, usize)>, bool) {
+    let mut pairs = Vec::new();
+    let mut first_candidate = 0;
+
+    for &new_index in inserted {
+        if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            return (pairs, false);
+        }
+
+        let new_content = new_units[new_index];
+        let window_end = deleted.len().min(first_candidate + PAIRING_WINDOW);
+        let nearest = deleted[first_candidate..window_end]
+            .iter()
+            .position(|&old_index| {
+                old_units[old_index].similarity(new_content) >= f32::from(threshold)
+            });
+
+        if let Some(offset) = nearest {
+            pairs.push((new_index, deleted[first_candidate + offset]));
+            first_candidate += offset + 1;
+        }
+    }
+
+    (pairs, true)
 }
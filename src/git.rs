@@ -0,0 +1,225 @@
+use crate::types::{BlameError, BlameRevision};
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+use std::rc::Rc;
+
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Commit metadata attached to each revision loaded from a git repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCommit {
+    /// Full commit hash
+    pub hash: String,
+    /// Author name
+    pub author: String,
+    /// Author email
+    pub author_email: String,
+    /// Author time as seconds since the Unix epoch
+    pub author_time: i64,
+    /// First line of the commit message
+    pub summary: String,
+    /// Path of the file at this commit, relative to the repository root
+    pub path: String,
+}
+
+/// The history of a single file in a git repository, ordered oldest to newest.
+///
+/// Revisions are gathered by spawning the `git` executable, which must be on `PATH`.
+#[derive(Debug, Clone)]
+pub struct GitHistory {
+    contents: Vec<String>,
+    commits: Vec<Rc<GitCommit>>,
+}
+
+impl GitHistory {
+    /// Loads every commit reachable from `rev` that touched `path`, following renames.
+    ///
+    /// # Arguments
+    ///
+    /// * `repo` - Path to the root of the repository work tree
+    /// * `path` - Path of the file relative to the repository root, as of `rev`
+    /// * `rev` - Starting revision, such as `HEAD`, a branch name or a commit hash
+    ///
+    /// # Errors
+    ///
+    /// Returns `BlameError::Git` if `rev` starts with `-`, git fails to run or the file has no
+    /// history.
+    /// Returns `BlameError::InvalidUtf8` if a revision's content is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use blame_rs::{blame, GitHistory};
+    ///
+    /// let history = GitHistory::load(".", "src/lib.rs", "HEAD")?;
+    /// let revisions = history.revisions();
+    /// let result = blame(&revisions)?;
+    ///
+    /// for line in result.lines() {
+    ///     println!("{} {}", &line.revision_metadata.hash[..8], line.content.trim_end());
+    /// }
+    /// ```
+    pub fn load(
+        repo: impl AsRef<Path>,
+        path: impl AsRef<Path>,
+        rev: &str,
+    ) -> Result<Self, BlameError> {
+        let repo = repo.as_ref();
+        let path = path.as_ref();
+
+        // git would parse a leading `-` as an option, such as `--output=<file>`
+        if rev.starts_with('-') {
+            return Err(BlameError::Git(format!("invalid revision {:?}", rev)));
+        }
+
+        let log = run_git(
+            repo,
+            [
+                OsStr::new("-c"),
+                OsStr::new("core.quotePath=false"),
+                OsStr::new("log"),
+                OsStr::new("--follow"),
+                OsStr::new("--no-color"),
+                OsStr::new("--name-status"),
+                OsStr::new("--format=%x1e%H%x1f%an%x1f%ae%x1f%at%x1f%s"),
+                OsStr::new(rev),
+                OsStr::new("--"),
+                path.as_os_str(),
+            ],
+        )?;
+
+        let mut commits = parse_log(&log, &to_git_path(path))?;
+        if commits.is_empty() {
+            return Err(BlameError::Git(format!(
+                "no history for {} at {}",
+                path.display(),
+                rev
+            )));
+        }
+
+        // git log lists newest first
+        commits.reverse();
+
+        let contents = commits
+            .iter()
+            .enumerate()
+            .map(|(revision_index, commit)| {
+                let object = format!("{}:{}", commit.hash, commit.path);
+                let bytes = run_git(repo, ["cat-file", "blob", object.as_str()])?;
+                String::from_utf8(bytes).map_err(|err| BlameError::InvalidUtf8 {
+                    revision_index,
+                    valid_up_to: err.utf8_error().valid_up_to(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            contents,
+            commits: commits.into_iter().map(Rc::new).collect(),
+        })
+    }
+
+    /// Builds blame revisions borrowing from this history, oldest first.
+    pub fn revisions(&self) -> Vec<BlameRevision<'_, GitCommit>> {
+        self.contents
+            .iter()
+            .zip(&self.commits)
+            .map(|(content, commit)| BlameRevision {
+                content,
+                metadata: Rc::clone(commit),
+            })
+            .collect()
+    }
+
+    /// Commits that touched the file, oldest first.
+    pub fn commits(&self) -> &[Rc<GitCommit>] {
+        &self.commits
+    }
+
+    pub fn len(&self) -> usize {
+        self.commits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+}
+
+fn run_git<I, S>(repo: &Path, args: I) -> Result<Vec<u8>, BlameError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|err| BlameError::Git(format!("failed to run git: {}", err)))?;
+
+    if !output.status.success() {
+        return Err(BlameError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+fn to_git_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Walks `git log --name-status` output newest to oldest, tracking the file's name
+// across renames. Commits without a status line (merges) keep the current name.
+fn parse_log(log: &[u8], path: &str) -> Result<Vec<GitCommit>, BlameError> {
+    let log = std::str::from_utf8(log)
+        .map_err(|_| BlameError::Git("git log output is not valid UTF-8".to_string()))?;
+
+    let mut current_path = path.to_string();
+    let mut commits = Vec::new();
+
+    for record in log.split(RECORD_SEPARATOR).skip(1) {
+        let mut lines = record.lines();
+        let header = lines.next().unwrap_or_default();
+        let fields: Vec<&str> = header.splitn(5, FIELD_SEPARATOR).collect();
+        let [hash, author, author_email, author_time, summary] = fields[..] else {
+            return Err(BlameError::Git(format!(
+                "unexpected git log record: {:?}",
+                header
+            )));
+        };
+
+        let status = lines.find(|line| !line.is_empty());
+        let mut commit_path = current_path.clone();
+
+        if let Some(status) = status {
+            let parts: Vec<&str> = status.split('\t').collect();
+            match parts[..] {
+                [kind, ..] if kind.starts_with('D') => continue,
+                [kind, old, new] if kind.starts_with('R') || kind.starts_with('C') => {
+                    commit_path = new.to_string();
+                    current_path = old.to_string();
+                }
+                [_, name] => commit_path = name.to_string(),
+                _ => {}
+            }
+        }
+
+        commits.push(GitCommit {
+            hash: hash.to_string(),
+            author: author.to_string(),
+            author_email: author_email.to_string(),
+            author_time: author_time.parse().unwrap_or_default(),
+            summary: summary.to_string(),
+            path: commit_path,
+        });
+    }
+
+    Ok(commits)
+}
@@ -0,0 +1,152 @@
+use crate::blame::{Origin, track_origins};
+use crate::tokens::{LineTokenizer, Tokenizer};
+use crate::types::{BlameError, BlameOptions, LineHistory, LineHistoryEntry, Revision};
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+/// Similarity threshold used for histories when the options leave pairing disabled
+const DEFAULT_SIMILARITY_THRESHOLD: u8 = 50;
+
+/// Computes the history of one line of the final revision, like `git log -L`.
+///
+/// Lines are tracked forward through the revisions as in
+/// [`blame_with_options`](crate::blame_with_options), and edited lines are paired with the
+/// lines they replaced by similarity. Following those pairs back gives every revision that
+/// introduced or edited the line, with its content at that revision.
+///
+/// Pairing uses `options.similarity_threshold`, or 50% when it is `None`.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+/// * `line_number` - Index of the line in the final revision
+///
+/// # Returns
+///
+/// Returns a `LineHistory` whose entries are ordered newest first; the last entry is the
+/// revision that first introduced the line.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::LineOutOfRange` if `line_number` is out of range, or
+/// `BlameError::DiffInvariantViolated` if diff invariants
+/// are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{line_history, BlameOptions};
+///
+/// let history = line_history(&revisions, BlameOptions::default(), 41)?;
+/// for entry in history.iter() {
+///     println!("{}: {}", entry.revision_metadata.hash, entry.content.trim_end());
+/// }
+/// ```
+pub fn line_history<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+    line_number: usize,
+) -> Result<LineHistory<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    let origins = track_line_origins(revisions, options)?;
+    let origin = origins.get(line_number).ok_or(BlameError::LineOutOfRange {
+        line_number,
+        lines: origins.len(),
+    })?;
+
+    Ok(history_of(line_number, origin))
+}
+
+/// Computes the history of every line of the final revision in a single pass.
+///
+/// See [`line_history`] for how histories are built.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns one `LineHistory` per line of the final revision, in line order.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{line_histories, BlameOptions};
+///
+/// for history in line_histories(&revisions, BlameOptions::default())? {
+///     println!("line {} was touched {} times", history.line_number() + 1, history.len());
+/// }
+/// ```
+pub fn line_histories<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+) -> Result<Vec<LineHistory<'a, T>>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    let origins = track_line_origins(revisions, options)?;
+
+    Ok(origins
+        .iter()
+        .enumerate()
+        .map(|(line_number, origin)| history_of(line_number, origin))
+        .collect())
+}
+
+fn track_line_origins<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+) -> Result<Vec<Origin<'a, T>>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    if revisions.is_empty() {
+        return Err(BlameError::EmptyRevisions);
+    }
+
+    let options = BlameOptions {
+        similarity_threshold: Some(
+            options
+                .similarity_threshold
+                .unwrap_or(DEFAULT_SIMILARITY_THRESHOLD),
+        ),
+        ..options
+    };
+
+    let tracked = track_origins(
+        revisions,
+        options,
+        |content| LineTokenizer.tokenize(content),
+        &mut |_| ControlFlow::Continue(()),
+    )?;
+    Ok(tracked.origins)
+}
+
+// Walks the chain of replaced lines from the final origin back to the first introduction
+fn history_of<'a, T>(line_number: usize, origin: &Origin<'a, T>) -> LineHistory<'a, T> {
+    let mut entries = Vec::new();
+    let mut current = Some(origin);
+
+    while let Some(origin) = current {
+        entries.push(LineHistoryEntry {
+            revision_index: origin.revision_index,
+            line_number: origin.line_number,
+            content: origin.content,
+            revision_metadata: Rc::clone(&origin.metadata),
+        });
+        current = origin.previous.as_deref().map(|previous| &previous.origin);
+    }
+
+    LineHistory::new(line_number, entries)
+}
@@ -10,9 +10,28 @@
 //! - **Generic metadata**: Attach any metadata type to revisions (commit hashes, authors, timestamps, etc.)
 //! - **Multiple diff algorithms**: Support for Myers and Patience algorithms via the `similar` crate
 //! - **Forward tracking**: Efficiently traces line origins from oldest to newest revision
+//! - **Backward tracking**: Optionally walk from the newest revision back, stopping once every line is attributed
 //! - **Zero-copy optimization**: Uses string slices (`&str`) to avoid unnecessary allocations
+//! - **Owned content**: Blame `OwnedRevision`s holding `String`, `Arc<str>` or `Cow<str>` directly
 //! - **Shared metadata**: Reference-counted metadata sharing reduces memory usage
 //! - **Pre-allocated vectors**: Minimizes heap allocations during processing
+//! - **Compact origins**: Run-length encoded origin spans with `u32` revision indices while tracking
+//! - **Interned, trimmed diffs**: Lines are interned into integer ids per step, and Myers diffs only the edited middle
+//! - **Byte-level input**: Blame `&[u8]` content that isn't valid UTF-8 with `blame_bytes`
+//! - **Streaming input**: Feed owned revisions from an iterator, keeping only two of them alive
+//! - **Git backend** (`git` feature): Load a file's history from a local repository, following renames
+//! - **Word-level blame**: Track origins per token within lines, so small edits don't claim whole lines
+//! - **Character-level blame**: Run-length encoded origin spans with UTF-8 correct boundaries
+//! - **Custom tokenizers**: Track sentences, DSL tokens or any other unit via the `Tokenizer` trait
+//! - **Previous-line tracking**: Pair edited lines with the lines they replaced by similarity
+//! - **Line history**: Every revision that introduced or edited a line, like `git log -L`
+//! - **Blame snapshots**: Blame at every (or chosen) revision from a single forward pass
+//! - **Guardrails**: Limits on revisions, lines, line length and diff time, plus binary detection
+//! - **Deadline fallback**: Approximate slow diffs instead of failing, recording which revisions were affected
+//! - **No-op revisions**: Skip identical revisions without diffing and list revisions with no surviving lines
+//! - **History validation**: Optionally warn about duplicated revisions and metadata shared across contents
+//! - **Progress and cancellation**: Report revision i of n to a callback that can stop the blame early
+//! - **Age heatmaps**: Render blame output colored by line age for terminals (ANSI) or HTML
 //!
 //! ## Example
 //!
@@ -49,8 +68,30 @@
 //! }
 //! ```
 
+mod backward;
 mod blame;
+#[cfg(feature = "git")]
+mod git;
+mod history;
+mod render;
+mod snapshot;
+mod tokens;
 mod types;
 
-pub use blame::{blame, blame_with_options};
-pub use types::{BlameError, BlameLine, BlameOptions, BlameResult, BlameRevision, DiffAlgorithm};
+pub use blame::{
+    blame, blame_bytes, blame_iter, blame_with_options, blame_with_progress, blame_with_tokenizer,
+};
+#[cfg(feature = "git")]
+pub use git::{GitCommit, GitHistory};
+pub use history::{line_histories, line_history};
+pub use render::{HeatmapFormat, render_heatmap, render_heatmap_by};
+pub use snapshot::{blame_snapshots, blame_snapshots_at};
+pub use tokens::{
+    CharTokenizer, LineTokenizer, Tokenizer, WordTokenizer, blame_chars, blame_words,
+};
+pub use types::{
+    BlameError, BlameLimits, BlameLine, BlameOptions, BlameResult, BlameRevision, BlameSpan,
+    BlameStrategy, ByteBlameResult, ByteRevision, CharBlameResult, DeadlineFallback, DiffAlgorithm,
+    DiffInvariant, HistoryWarning, LineHistory, LineHistoryEntry, OwnedBlameResult, OwnedRevision,
+    PreviousLine, Progress, Revision, WordBlameLine, WordBlameResult,
+};
@@ -0,0 +1,143 @@
+use crate::types::{BlameLine, BlameResult};
+use std::fmt::Write;
+
+/// Age buckets from oldest to newest, modeled after GitHub's blame age bar
+const AGE_PALETTE: [(u8, u8, u8); 10] = [
+    (0xff, 0xf1, 0xe5),
+    (0xff, 0xd8, 0xb5),
+    (0xff, 0xb7, 0x7c),
+    (0xfb, 0x8f, 0x44),
+    (0xe1, 0x6f, 0x24),
+    (0xbc, 0x4c, 0x00),
+    (0x95, 0x38, 0x00),
+    (0x76, 0x2c, 0x00),
+    (0x5c, 0x22, 0x00),
+    (0x47, 0x17, 0x00),
+];
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Output format for heatmap rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatmapFormat {
+    /// ANSI 24-bit color escape sequences for terminals (default)
+    #[default]
+    Ansi,
+    /// HTML table with inline styles
+    Html,
+}
+
+/// Renders a blame result with each line colored by the age of its originating revision.
+///
+/// Age is measured by revision index: lines from the oldest revision present in the
+/// result get the lightest color and lines from the newest revision get the darkest.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame, render_heatmap, HeatmapFormat};
+///
+/// let result = blame(&revisions)?;
+/// print!("{}", render_heatmap(&result, HeatmapFormat::Ansi));
+/// ```
+pub fn render_heatmap<T>(result: &BlameResult<'_, T>, format: HeatmapFormat) -> String {
+    render_heatmap_by(result, format, |line| line.revision_index as i64)
+}
+
+/// Renders a heatmap using a custom age key, such as a timestamp stored in the metadata.
+///
+/// Larger keys are treated as newer. Colors are scaled between the smallest and largest
+/// key found in the result.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{render_heatmap_by, HeatmapFormat};
+///
+/// let html = render_heatmap_by(&result, HeatmapFormat::Html, |line| {
+///     line.revision_metadata.timestamp
+/// });
+/// ```
+pub fn render_heatmap_by<T, F>(result: &BlameResult<'_, T>, format: HeatmapFormat, age: F) -> String
+where
+    F: Fn(&BlameLine<'_, T>) -> i64,
+{
+    let ages: Vec<i64> = result.iter().map(&age).collect();
+    let oldest = ages.iter().copied().min().unwrap_or(0);
+    let newest = ages.iter().copied().max().unwrap_or(0);
+
+    let revision_width = result
+        .iter()
+        .map(|line| line.revision_index)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    let line_width = result.len().to_string().len();
+
+    let mut out = String::new();
+
+    if format == HeatmapFormat::Html {
+        out.push_str("<table class=\"blame-heatmap\">\n");
+    }
+
+    for (line, &line_age) in result.iter().zip(&ages) {
+        let (r, g, b) = AGE_PALETTE[age_bucket(line_age, oldest, newest)];
+        let content = line.content.trim_end_matches(['\r', '\n']);
+
+        match format {
+            HeatmapFormat::Ansi => {
+                let _ = writeln!(
+                    out,
+                    "\x1b[48;2;{r};{g};{b}m  {ANSI_RESET} r{:<revision_width$} {:>line_width$} | {}",
+                    line.revision_index,
+                    line.line_number + 1,
+                    content
+                );
+            }
+            HeatmapFormat::Html => {
+                let _ = writeln!(
+                    out,
+                    "<tr><td class=\"age\" style=\"background-color:#{r:02x}{g:02x}{b:02x}\"></td>\
+                     <td class=\"revision\">r{}</td><td class=\"line-number\">{}</td>\
+                     <td class=\"code\"><pre>{}</pre></td></tr>",
+                    line.revision_index,
+                    line.line_number + 1,
+                    escape_html(content)
+                );
+            }
+        }
+    }
+
+    if format == HeatmapFormat::Html {
+        out.push_str("</table>\n");
+    }
+
+    out
+}
+
+fn age_bucket(age: i64, oldest: i64, newest: i64) -> usize {
+    let last = AGE_PALETTE.len() - 1;
+    if newest <= oldest {
+        return last;
+    }
+
+    let offset = i128::from(age) - i128::from(oldest);
+    let span = i128::from(newest) - i128::from(oldest);
+    (offset * last as i128 / span) as usize
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,132 @@
+use crate::blame::{OriginTracker, check_revision_count, to_blame_result};
+use crate::tokens::{LineTokenizer, Tokenizer};
+use crate::types::{BlameError, BlameOptions, BlameResult, Revision};
+use std::rc::Rc;
+
+/// Computes the blame of every revision in a single forward pass.
+///
+/// Equivalent to calling [`blame_with_options`](crate::blame_with_options) on
+/// `revisions[..=k]` for every `k`, but each diff is computed once and the snapshots share
+/// the revisions' metadata `Rc`s.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns one `BlameResult` per revision, in the same order as `revisions`.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_snapshots, BlameOptions};
+///
+/// let snapshots = blame_snapshots(&revisions, BlameOptions::default())?;
+/// let blame_at_third_revision = &snapshots[2];
+/// ```
+pub fn blame_snapshots<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+) -> Result<Vec<BlameResult<'a, T>>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    let indices: Vec<usize> = (0..revisions.len()).collect();
+    blame_snapshots_at(revisions, options, &indices)
+}
+
+/// Computes the blame at chosen revisions in a single forward pass.
+///
+/// Revisions after the last requested index are never diffed.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+/// * `indices` - Indices of the revisions to blame, in any order
+///
+/// # Returns
+///
+/// Returns one `BlameResult` per entry of `indices`, in the same order. The result for
+/// index `k` is the blame of `revisions[..=k]`.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::RevisionOutOfRange` if an index is out of range, or
+/// `BlameError::DiffInvariantViolated` if diff invariants are
+/// violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_snapshots_at, BlameOptions};
+///
+/// // Blame at the first, middle and last revisions
+/// let middle = revisions.len() / 2;
+/// let snapshots = blame_snapshots_at(&revisions, BlameOptions::default(), &[0, middle, revisions.len() - 1])?;
+/// ```
+pub fn blame_snapshots_at<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+    indices: &[usize],
+) -> Result<Vec<BlameResult<'a, T>>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    if revisions.is_empty() {
+        return Err(BlameError::EmptyRevisions);
+    }
+
+    check_revision_count(revisions.len(), &options.limits)?;
+
+    if let Some(&index) = indices.iter().find(|&&index| index >= revisions.len()) {
+        return Err(BlameError::RevisionOutOfRange {
+            revision_index: index,
+            revisions: revisions.len(),
+        });
+    }
+
+    // Positions in `indices`, ordered by the revision they ask for
+    let mut order: Vec<usize> = (0..indices.len()).collect();
+    order.sort_by_key(|&position| indices[position]);
+    let mut pending = order.into_iter().peekable();
+
+    let mut snapshots: Vec<Option<BlameResult<'a, T>>> = indices.iter().map(|_| None).collect();
+    let mut tracker = OriginTracker::new(
+        LineTokenizer.tokenize(revisions[0].content()),
+        Rc::clone(revisions[0].metadata()),
+        options,
+    )?;
+
+    loop {
+        while let Some(position) =
+            pending.next_if(|&position| indices[position] == tracker.revision_index())
+        {
+            let snapshot = to_blame_result(tracker.origins())
+                .with_revision_count(tracker.revision_index() + 1)
+                .with_report(tracker.report())
+                .with_empty_revisions(tracker.empty_revisions().to_vec());
+            snapshots[position] = Some(snapshot);
+        }
+
+        if pending.peek().is_none() {
+            break;
+        }
+
+        let revision = &revisions[tracker.revision_index() + 1];
+        tracker.advance(
+            LineTokenizer.tokenize(revision.content()),
+            Rc::clone(revision.metadata()),
+        )?;
+    }
+
+    Ok(snapshots.into_iter().flatten().collect())
+}
@@ -0,0 +1,290 @@
+use crate::blame::{Origin, track_origins};
+use crate::types::{
+    BlameError, BlameOptions, BlameSpan, CharBlameResult, Revision, WordBlameLine, WordBlameResult,
+};
+use std::ops::{ControlFlow, Range};
+
+/// Splits revision content into the units tracked by blame.
+///
+/// Tokens should be contiguous slices of `content` that concatenate back to it exactly;
+/// span-based results rely on this to compute byte ranges. Lines are the default unit.
+///
+/// Functions with the signature `fn(&str) -> Vec<&str>` implement this trait, as do
+/// closures coerced to that function pointer type.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_with_tokenizer, BlameOptions};
+///
+/// // Blame Markdown prose sentence by sentence
+/// fn sentences(content: &str) -> Vec<&str> {
+///     content.split_inclusive(['.', '!', '?']).collect()
+/// }
+///
+/// let result = blame_with_tokenizer(&revisions, BlameOptions::default(), &sentences)?;
+/// ```
+pub trait Tokenizer {
+    fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str>;
+}
+
+impl<F> Tokenizer for F
+where
+    F: for<'a> Fn(&'a str) -> Vec<&'a str>,
+{
+    fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        self(content)
+    }
+}
+
+/// Splits content into lines, keeping each line's terminator (the default unit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LineTokenizer;
+
+impl Tokenizer for LineTokenizer {
+    fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        content.split_inclusive('\n').collect()
+    }
+}
+
+/// Splits content into words, whitespace runs, single punctuation characters and line
+/// terminators (`\n` or `\r\n`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WordTokenizer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Space,
+    Single,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else if c.is_whitespace() && c != '\n' {
+        CharClass::Space
+    } else {
+        CharClass::Single
+    }
+}
+
+impl Tokenizer for WordTokenizer {
+    fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        let mut tokens = Vec::new();
+        let mut start = 0;
+
+        while start < content.len() {
+            let rest = &content[start..];
+            let len = if rest.starts_with("\r\n") {
+                2
+            } else {
+                let mut chars = rest.char_indices();
+                let (_, first) = chars.next().unwrap_or_default();
+                let class = char_class(first);
+
+                if class == CharClass::Single {
+                    first.len_utf8()
+                } else {
+                    let boundary = chars.find(|&(idx, c)| {
+                        char_class(c) != class || rest[idx..].starts_with("\r\n")
+                    });
+                    boundary.map_or(rest.len(), |(idx, _)| idx)
+                }
+            };
+
+            tokens.push(&rest[..len]);
+            start += len;
+        }
+
+        tokens
+    }
+}
+
+/// Splits content into one token per `char`, so every boundary is a UTF-8 character boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharTokenizer;
+
+impl Tokenizer for CharTokenizer {
+    fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        content
+            .char_indices()
+            .map(|(idx, c)| &content[idx..idx + c.len_utf8()])
+            .collect()
+    }
+}
+
+// Appends `range` to the last span if it has the same origin, otherwise starts a new span.
+// Span content is filled in once the span is complete.
+fn push_span<'a, T>(spans: &mut Vec<BlameSpan<'a, T>>, range: Range<usize>, origin: Origin<'a, T>) {
+    match spans.last_mut() {
+        Some(span) if span.revision_index == origin.revision_index => {
+            span.range.end = range.end;
+        }
+        _ => spans.push(BlameSpan {
+            range,
+            content: "",
+            revision_index: origin.revision_index,
+            revision_metadata: origin.metadata,
+        }),
+    }
+}
+
+fn fill_span_content<'a, T>(content: &'a str, spans: &mut [BlameSpan<'a, T>]) {
+    for span in spans {
+        span.content = &content[span.range.clone()];
+    }
+}
+
+/// Performs a word-level blame, tracking the origin of each token within every line.
+///
+/// Content is split into words, whitespace runs, punctuation and line terminators, and
+/// tokens are tracked forward through the revisions the same way lines are in
+/// [`blame_with_options`](crate::blame_with_options). A typo fix only claims the word it
+/// touched instead of the whole line.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns a `WordBlameResult` with one entry per line of the final revision, each split
+/// into spans of adjacent tokens that share an origin.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_words, BlameOptions};
+///
+/// let result = blame_words(&revisions, BlameOptions::default())?;
+/// for line in result.lines() {
+///     for span in &line.spans {
+///         println!("{:?} from {}", span.content, span.revision_metadata.author);
+///     }
+/// }
+/// ```
+pub fn blame_words<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+) -> Result<WordBlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    if revisions.is_empty() {
+        return Err(BlameError::EmptyRevisions);
+    }
+
+    let tracked = track_origins(
+        revisions,
+        options,
+        |content| WordTokenizer.tokenize(content),
+        &mut |_| ControlFlow::Continue(()),
+    )?;
+    let content = revisions[revisions.len() - 1].content();
+
+    let mut lines = Vec::new();
+    let mut spans = Vec::new();
+    let mut line_start = 0;
+    let mut offset = 0;
+
+    for origin in tracked.origins {
+        let end = offset + origin.content.len();
+        let ends_line = origin.content.ends_with('\n');
+        push_span(&mut spans, offset..end, origin);
+        offset = end;
+
+        if ends_line {
+            fill_span_content(content, &mut spans);
+            lines.push(WordBlameLine {
+                line_number: lines.len(),
+                content: &content[line_start..end],
+                spans: std::mem::take(&mut spans),
+            });
+            line_start = end;
+        }
+    }
+
+    if !spans.is_empty() {
+        fill_span_content(content, &mut spans);
+        lines.push(WordBlameLine {
+            line_number: lines.len(),
+            content: &content[line_start..],
+            spans,
+        });
+    }
+
+    Ok(WordBlameResult::new(lines).with_report(tracked.report))
+}
+
+/// Performs a character-level blame, tracking the origin of every character.
+///
+/// Characters are Unicode scalar values, so span boundaries always fall on UTF-8 character
+/// boundaries. Adjacent characters with the same origin are merged, producing a
+/// run-length encoded list of spans over the final revision's content.
+///
+/// Diffing per character is considerably more expensive than per line; prefer
+/// [`blame_words`] for large documents.
+///
+/// # Arguments
+///
+/// * `revisions` - A slice of revisions ordered chronologically (oldest first, newest last)
+/// * `options` - Configuration options for the blame operation
+///
+/// # Returns
+///
+/// Returns a `CharBlameResult` whose spans cover the final content in order.
+///
+/// # Errors
+///
+/// Returns `BlameError::EmptyRevisions` if the revisions slice is empty.
+/// Returns `BlameError::DiffInvariantViolated` if diff invariants are violated.
+///
+/// # Example
+///
+/// ```ignore
+/// use blame_rs::{blame_chars, BlameOptions};
+///
+/// let result = blame_chars(&revisions, BlameOptions::default())?;
+///
+/// // Who wrote the character under the cursor?
+/// if let Some(span) = result.span_at(cursor_byte_offset) {
+///     println!("written by {}", span.revision_metadata.author);
+/// }
+/// ```
+pub fn blame_chars<'a, T, R>(
+    revisions: &'a [R],
+    options: BlameOptions,
+) -> Result<CharBlameResult<'a, T>, BlameError>
+where
+    R: Revision<Metadata = T>,
+{
+    if revisions.is_empty() {
+        return Err(BlameError::EmptyRevisions);
+    }
+
+    let tracked = track_origins(
+        revisions,
+        options,
+        |content| CharTokenizer.tokenize(content),
+        &mut |_| ControlFlow::Continue(()),
+    )?;
+    let content = revisions[revisions.len() - 1].content();
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for origin in tracked.origins {
+        let end = offset + origin.content.len();
+        push_span(&mut spans, offset..end, origin);
+        offset = end;
+    }
+    fill_span_content(content, &mut spans);
+
+    Ok(CharBlameResult::new(content, spans).with_report(tracked.report))
+}
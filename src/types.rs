@@ -1,4 +1,6 @@
+use std::ops::Range;
 use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct BlameRevision<'a, T> {
@@ -6,29 +8,448 @@ pub struct BlameRevision<'a, T> {
     pub metadata: Rc<T>,
 }
 
+/// A revision whose content is raw bytes, for files that are not valid UTF-8
 #[derive(Debug, Clone)]
-pub struct BlameLine<'a, T> {
+pub struct ByteRevision<'a, T> {
+    pub content: &'a [u8],
+    pub metadata: Rc<T>,
+}
+
+/// A revision whose content owns its text, such as a `String`, `Arc<str>` or `Cow<str>`
+///
+/// Lets histories loaded from storage be blamed directly, without keeping a separate
+/// `Vec<String>` alive to borrow from.
+#[derive(Debug, Clone)]
+pub struct OwnedRevision<T, C = String> {
+    pub content: C,
+    pub metadata: Rc<T>,
+}
+
+/// Revision input accepted by the slice-based blame functions
+///
+/// Implemented by [`BlameRevision`] (borrowed content) and [`OwnedRevision`] (owned content).
+/// Results borrow line content from the revisions, so they live as long as the slice;
+/// [`blame_iter`](crate::blame_iter) takes them by value instead.
+pub trait Revision {
+    type Metadata;
+
+    fn content(&self) -> &str;
+
+    fn metadata(&self) -> &Rc<Self::Metadata>;
+}
+
+impl<T> Revision for BlameRevision<'_, T> {
+    type Metadata = T;
+
+    fn content(&self) -> &str {
+        self.content
+    }
+
+    fn metadata(&self) -> &Rc<T> {
+        &self.metadata
+    }
+}
+
+impl<T, C> Revision for OwnedRevision<T, C>
+where
+    C: AsRef<str>,
+{
+    type Metadata = T;
+
+    fn content(&self) -> &str {
+        self.content.as_ref()
+    }
+
+    fn metadata(&self) -> &Rc<T> {
+        &self.metadata
+    }
+}
+
+/// A unit of the final revision with its origin
+///
+/// `C` is the content type: `str` for text blame, `[u8]` for [`blame_bytes`](crate::blame_bytes).
+#[derive(Debug)]
+pub struct BlameLine<'a, T, C: ?Sized = str> {
     pub line_number: usize,
-    pub content: &'a str,
+    pub content: &'a C,
+    /// Index of the revision that introduced this line
+    pub revision_index: usize,
+    pub revision_metadata: Rc<T>,
+    /// The line this one replaced, when it was an edit of an older line
+    ///
+    /// Only populated when [`BlameOptions::similarity_threshold`] is set.
+    pub previous: Option<PreviousLine<'a, T, C>>,
+}
+
+impl<T, C: ?Sized> Clone for BlameLine<'_, T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            line_number: self.line_number,
+            content: self.content,
+            revision_index: self.revision_index,
+            revision_metadata: Rc::clone(&self.revision_metadata),
+            previous: self.previous.clone(),
+        }
+    }
+}
+
+/// The line an edited line replaced, in the revision just before the edit
+///
+/// Mirrors the `previous` field of `git blame --porcelain`: blaming `revision_index` and
+/// looking at `line_number` continues the line's history past the edit.
+#[derive(Debug)]
+pub struct PreviousLine<'a, T, C: ?Sized = str> {
+    /// Index of the revision just before the edit
+    pub revision_index: usize,
+    /// Index of the replaced line in that revision
+    pub line_number: usize,
+    /// Content of the replaced line
+    pub content: &'a C,
     pub revision_metadata: Rc<T>,
 }
 
+impl<T, C: ?Sized> Clone for PreviousLine<'_, T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            revision_index: self.revision_index,
+            line_number: self.line_number,
+            content: self.content,
+            revision_metadata: Rc::clone(&self.revision_metadata),
+        }
+    }
+}
+
+/// What a blame run reports about itself, held the same way by every result type
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlameReport {
+    /// Revisions whose diff hit a deadline and was approximated
+    pub(crate) approximated_revisions: Vec<usize>,
+    /// Suspicious patterns found in the history, when validating
+    pub(crate) warnings: Vec<HistoryWarning>,
+}
+
 /// The result of a blame operation, containing all lines with their origin information
+#[derive(Debug)]
+pub struct BlameResult<'a, T, C: ?Sized = str> {
+    lines: Vec<BlameLine<'a, T, C>>,
+    revision_count: usize,
+    report: BlameReport,
+    empty_revisions: Vec<usize>,
+}
+
+impl<T, C: ?Sized> Clone for BlameResult<'_, T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            lines: self.lines.clone(),
+            revision_count: self.revision_count,
+            report: self.report.clone(),
+            empty_revisions: self.empty_revisions.clone(),
+        }
+    }
+}
+
+impl<'a, T, C: ?Sized> BlameResult<'a, T, C> {
+    pub fn new(lines: Vec<BlameLine<'a, T, C>>) -> Self {
+        // Without the history at hand, assume it ends at the newest revision with lines
+        let revision_count = lines
+            .iter()
+            .map(|line| line.revision_index + 1)
+            .max()
+            .unwrap_or(0);
+        Self {
+            lines,
+            revision_count,
+            report: BlameReport::default(),
+            empty_revisions: Vec::new(),
+        }
+    }
+
+    pub fn lines(&self) -> &[BlameLine<'a, T, C>] {
+        &self.lines
+    }
+
+    pub fn get_line(&self, index: usize) -> Option<&BlameLine<'a, T, C>> {
+        self.lines.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlameLine<'a, T, C>> {
+        self.lines.iter()
+    }
+
+    /// Indices of revisions whose diff hit a deadline and was approximated
+    ///
+    /// Empty unless [`BlameLimits::deadline_fallback`] allows approximation. Lines introduced
+    /// by these revisions may be attributed to them even if they are older.
+    pub fn approximated_revisions(&self) -> &[usize] {
+        &self.report.approximated_revisions
+    }
+
+    pub(crate) fn with_report(mut self, report: BlameReport) -> Self {
+        self.report = report;
+        self
+    }
+
+    /// Suspicious patterns found in the history when [`BlameOptions::validate`] is set
+    pub fn warnings(&self) -> &[HistoryWarning] {
+        &self.report.warnings
+    }
+
+    /// Number of revisions that were blamed
+    pub fn revision_count(&self) -> usize {
+        self.revision_count
+    }
+
+    /// Indices of revisions that no line of the final revision is attributed to
+    ///
+    /// These revisions only deleted lines, changed nothing, or added lines that were
+    /// later removed or rewritten.
+    pub fn revisions_without_lines(&self) -> Vec<usize> {
+        let mut has_lines = vec![false; self.revision_count];
+        for line in &self.lines {
+            has_lines[line.revision_index] = true;
+        }
+        (0..self.revision_count)
+            .filter(|&revision_index| !has_lines[revision_index])
+            .collect()
+    }
+
+    /// Indices of revisions that added no lines when they were applied, in order
+    ///
+    /// Empty unless [`BlameOptions::report_empty_revisions`] is set. Unlike
+    /// [`revisions_without_lines`](Self::revisions_without_lines), a revision whose lines
+    /// were removed later is not listed here.
+    pub fn empty_revisions(&self) -> &[usize] {
+        &self.empty_revisions
+    }
+
+    pub(crate) fn with_revision_count(mut self, revision_count: usize) -> Self {
+        self.revision_count = revision_count;
+        self
+    }
+
+    pub(crate) fn with_empty_revisions(mut self, revisions: Vec<usize>) -> Self {
+        self.empty_revisions = revisions;
+        self
+    }
+}
+
+/// The result of [`blame_iter`](crate::blame_iter), owning everything it reports
+///
+/// Holds the last revision, whose lines are blamed, and a copy of every replaced line, so the
+/// rest of the history can be dropped while it streams by. [`result`](Self::result) borrows
+/// it as a [`BlameResult`].
+#[derive(Debug)]
+pub struct OwnedBlameResult<R: Revision> {
+    revision: R,
+    lines: Vec<OwnedLine<R::Metadata>>,
+    revision_count: usize,
+    report: BlameReport,
+    empty_revisions: Vec<usize>,
+}
+
+/// Origin of one line of an [`OwnedBlameResult`], by its byte range in the last revision
+#[derive(Debug)]
+pub(crate) struct OwnedLine<T> {
+    pub(crate) range: Range<usize>,
+    pub(crate) revision_index: usize,
+    pub(crate) revision_metadata: Rc<T>,
+    pub(crate) previous: Option<Rc<OwnedPrevious<T>>>,
+}
+
+/// A replaced line, with a copy of its content taken before its revision was dropped
+#[derive(Debug)]
+pub(crate) struct OwnedPrevious<T> {
+    pub(crate) revision_index: usize,
+    pub(crate) line_number: usize,
+    pub(crate) content: Box<str>,
+    pub(crate) revision_metadata: Rc<T>,
+}
+
+impl<R: Revision> OwnedBlameResult<R> {
+    pub(crate) fn new(
+        revision: R,
+        lines: Vec<OwnedLine<R::Metadata>>,
+        revision_count: usize,
+    ) -> Self {
+        Self {
+            revision,
+            lines,
+            revision_count,
+            report: BlameReport::default(),
+            empty_revisions: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_report(mut self, report: BlameReport) -> Self {
+        self.report = report;
+        self
+    }
+
+    pub(crate) fn with_empty_revisions(mut self, revisions: Vec<usize>) -> Self {
+        self.empty_revisions = revisions;
+        self
+    }
+
+    /// Borrows the blame as a [`BlameResult`] over the last revision's content
+    pub fn result(&self) -> BlameResult<'_, R::Metadata> {
+        let content = self.revision.content();
+        let lines = self
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(line_number, line)| BlameLine {
+                line_number,
+                content: &content[line.range.clone()],
+                revision_index: line.revision_index,
+                revision_metadata: Rc::clone(&line.revision_metadata),
+                previous: line.previous.as_deref().map(|previous| PreviousLine {
+                    revision_index: previous.revision_index,
+                    line_number: previous.line_number,
+                    content: &*previous.content,
+                    revision_metadata: Rc::clone(&previous.revision_metadata),
+                }),
+            })
+            .collect();
+
+        BlameResult::new(lines)
+            .with_revision_count(self.revision_count)
+            .with_report(self.report.clone())
+            .with_empty_revisions(self.empty_revisions.clone())
+    }
+
+    /// The last revision, whose lines were blamed
+    pub fn revision(&self) -> &R {
+        &self.revision
+    }
+
+    pub fn into_revision(self) -> R {
+        self.revision
+    }
+}
+
+impl<'a, T, C: ?Sized> IntoIterator for BlameResult<'a, T, C> {
+    type Item = BlameLine<'a, T, C>;
+    type IntoIter = std::vec::IntoIter<BlameLine<'a, T, C>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.into_iter()
+    }
+}
+
+/// The result of a byte-level blame: lines are byte slices that may not be valid UTF-8
+pub type ByteBlameResult<'a, T> = BlameResult<'a, T, [u8]>;
+
+/// One revision in the history of a line: where the line was introduced or last edited
+#[derive(Debug, Clone)]
+pub struct LineHistoryEntry<'a, T> {
+    /// Index of the revision that introduced or edited the line
+    pub revision_index: usize,
+    /// Index of the line within that revision
+    pub line_number: usize,
+    /// Content of the line at that revision
+    pub content: &'a str,
+    pub revision_metadata: Rc<T>,
+}
+
+/// The revisions that touched one line of the final revision, newest first
+#[derive(Debug, Clone)]
+pub struct LineHistory<'a, T> {
+    line_number: usize,
+    entries: Vec<LineHistoryEntry<'a, T>>,
+}
+
+impl<'a, T> LineHistory<'a, T> {
+    pub fn new(line_number: usize, entries: Vec<LineHistoryEntry<'a, T>>) -> Self {
+        Self {
+            line_number,
+            entries,
+        }
+    }
+
+    /// Index of the line in the final revision
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn entries(&self) -> &[LineHistoryEntry<'a, T>] {
+        &self.entries
+    }
+
+    /// The revision that first introduced the line, before any edits
+    pub fn introduced(&self) -> Option<&LineHistoryEntry<'a, T>> {
+        self.entries.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LineHistoryEntry<'a, T>> {
+        self.entries.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for LineHistory<'a, T> {
+    type Item = LineHistoryEntry<'a, T>;
+    type IntoIter = std::vec::IntoIter<LineHistoryEntry<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// A contiguous run of final content attributed to a single revision
 #[derive(Debug, Clone)]
-pub struct BlameResult<'a, T> {
-    lines: Vec<BlameLine<'a, T>>,
+pub struct BlameSpan<'a, T> {
+    /// Byte range of the span within the final revision's content
+    pub range: Range<usize>,
+    pub content: &'a str,
+    /// Index of the revision that introduced this span
+    pub revision_index: usize,
+    pub revision_metadata: Rc<T>,
+}
+
+/// A line of the final revision split into spans by origin
+#[derive(Debug, Clone)]
+pub struct WordBlameLine<'a, T> {
+    pub line_number: usize,
+    pub content: &'a str,
+    /// Spans covering the whole line in order; adjacent spans have different origins
+    pub spans: Vec<BlameSpan<'a, T>>,
 }
 
-impl<'a, T> BlameResult<'a, T> {
-    pub fn new(lines: Vec<BlameLine<'a, T>>) -> Self {
-        Self { lines }
+/// The result of a word-level blame operation
+#[derive(Debug, Clone)]
+pub struct WordBlameResult<'a, T> {
+    lines: Vec<WordBlameLine<'a, T>>,
+    report: BlameReport,
+}
+
+impl<'a, T> WordBlameResult<'a, T> {
+    pub fn new(lines: Vec<WordBlameLine<'a, T>>) -> Self {
+        Self {
+            lines,
+            report: BlameReport::default(),
+        }
     }
 
-    pub fn lines(&self) -> &[BlameLine<'a, T>] {
+    pub fn lines(&self) -> &[WordBlameLine<'a, T>] {
         &self.lines
     }
 
-    pub fn get_line(&self, index: usize) -> Option<&BlameLine<'a, T>> {
+    pub fn get_line(&self, index: usize) -> Option<&WordBlameLine<'a, T>> {
         self.lines.get(index)
     }
 
@@ -40,20 +461,113 @@ impl<'a, T> BlameResult<'a, T> {
         self.lines.is_empty()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &BlameLine<'a, T>> {
+    pub fn iter(&self) -> impl Iterator<Item = &WordBlameLine<'a, T>> {
         self.lines.iter()
     }
+
+    /// Indices of revisions whose diff hit a deadline and was approximated
+    ///
+    /// Empty unless [`BlameLimits::deadline_fallback`] allows approximation. Spans introduced
+    /// by these revisions may be attributed to them even if they are older.
+    pub fn approximated_revisions(&self) -> &[usize] {
+        &self.report.approximated_revisions
+    }
+
+    pub(crate) fn with_report(mut self, report: BlameReport) -> Self {
+        self.report = report;
+        self
+    }
+
+    /// Suspicious patterns found in the history when [`BlameOptions::validate`] is set
+    pub fn warnings(&self) -> &[HistoryWarning] {
+        &self.report.warnings
+    }
 }
 
-impl<'a, T> IntoIterator for BlameResult<'a, T> {
-    type Item = BlameLine<'a, T>;
-    type IntoIter = std::vec::IntoIter<BlameLine<'a, T>>;
+impl<'a, T> IntoIterator for WordBlameResult<'a, T> {
+    type Item = WordBlameLine<'a, T>;
+    type IntoIter = std::vec::IntoIter<WordBlameLine<'a, T>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.lines.into_iter()
     }
 }
 
+/// The result of a character-level blame operation: run-length encoded origin spans
+/// covering the final revision's content
+#[derive(Debug, Clone)]
+pub struct CharBlameResult<'a, T> {
+    content: &'a str,
+    spans: Vec<BlameSpan<'a, T>>,
+    report: BlameReport,
+}
+
+impl<'a, T> CharBlameResult<'a, T> {
+    pub fn new(content: &'a str, spans: Vec<BlameSpan<'a, T>>) -> Self {
+        Self {
+            content,
+            spans,
+            report: BlameReport::default(),
+        }
+    }
+
+    /// The final revision's content
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+
+    pub fn spans(&self) -> &[BlameSpan<'a, T>] {
+        &self.spans
+    }
+
+    /// Returns the span containing the given byte offset of the final content
+    pub fn span_at(&self, offset: usize) -> Option<&BlameSpan<'a, T>> {
+        let idx = self.spans.partition_point(|span| span.range.end <= offset);
+        self.spans
+            .get(idx)
+            .filter(|span| span.range.contains(&offset))
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlameSpan<'a, T>> {
+        self.spans.iter()
+    }
+
+    /// Indices of revisions whose diff hit a deadline and was approximated
+    ///
+    /// Empty unless [`BlameLimits::deadline_fallback`] allows approximation. Spans introduced
+    /// by these revisions may be attributed to them even if they are older.
+    pub fn approximated_revisions(&self) -> &[usize] {
+        &self.report.approximated_revisions
+    }
+
+    pub(crate) fn with_report(mut self, report: BlameReport) -> Self {
+        self.report = report;
+        self
+    }
+
+    /// Suspicious patterns found in the history when [`BlameOptions::validate`] is set
+    pub fn warnings(&self) -> &[HistoryWarning] {
+        &self.report.warnings
+    }
+}
+
+impl<'a, T> IntoIterator for CharBlameResult<'a, T> {
+    type Item = BlameSpan<'a, T>;
+    type IntoIter = std::vec::IntoIter<BlameSpan<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.into_iter()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DiffAlgorithm {
     /// Myers diff algorithm (default)
@@ -68,6 +582,123 @@ pub enum DiffAlgorithm {
 pub struct BlameOptions {
     /// Diff algorithm to use
     pub algorithm: DiffAlgorithm,
+    /// Minimum similarity, in percent, for pairing a replaced line with the line it replaced
+    ///
+    /// Deleted and inserted lines of the same hunk are paired in order when their content
+    /// (ignoring line terminators) is at least this similar, and the pairing is recorded in
+    /// [`BlameLine::previous`]. Each inserted line takes the nearest qualifying deleted line
+    /// within a few lines after the last pair, and pairing counts toward the step's diff
+    /// deadlines. Attribution itself is unchanged. `None` disables pairing.
+    pub similarity_threshold: Option<u8>,
+    /// Guardrails against inputs that would take too long to blame
+    pub limits: BlameLimits,
+    /// Check the history for duplicated revisions and misattached metadata while blaming,
+    /// reporting what was found as [`HistoryWarning`]s on the result
+    pub validate: bool,
+    /// Record the revisions that added no lines when applied, such as exact duplicates or
+    /// pure deletions, in [`BlameResult::empty_revisions`]
+    pub report_empty_revisions: bool,
+    /// Direction in which the history is walked; both give identical results
+    pub strategy: BlameStrategy,
+}
+
+/// Direction in which revisions are walked to attribute the final lines
+///
+/// Both strategies produce the same [`BlameResult`]; they differ only in how much of the
+/// history they have to read. Streaming ([`blame_iter`](crate::blame_iter)) and snapshot
+/// ([`blame_snapshots`](crate::blame_snapshots)) blame always walk forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlameStrategy {
+    /// Walk from the oldest revision to the newest, tracking every line of every revision
+    /// (default)
+    #[default]
+    Forward,
+    /// Walk from the newest revision back, tracking only the lines that survive into it, and
+    /// stop as soon as all of them are attributed (as `git blame` does)
+    ///
+    /// Revisions older than the oldest surviving line are never diffed, and their limits are
+    /// not checked. [`BlameOptions::validate`] still reads every revision, and
+    /// [`BlameOptions::report_empty_revisions`] turns early stopping off.
+    Backward,
+}
+
+/// A suspicious pattern in a revision history, reported when [`BlameOptions::validate`] is set
+///
+/// Warnings never change the blame itself; they point at input that was probably assembled
+/// by mistake, such as a revision fed twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryWarning {
+    /// Revision `revision_index` has exactly the same content as the revision before it
+    IdenticalToPrevious { revision_index: usize },
+
+    /// Revision `revision_index` shares its metadata `Rc` with revision `shared_with`,
+    /// although their contents differ
+    SharedMetadata {
+        revision_index: usize,
+        shared_with: usize,
+    },
+}
+
+/// Limits checked while blaming, so oversized or binary input fails fast instead of hanging
+///
+/// Line limits apply to the tracked units, so they count tokens rather than lines in
+/// word, character and custom-tokenizer blame. Every limit is disabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlameLimits {
+    /// Maximum number of revisions
+    pub max_revisions: Option<usize>,
+    /// Maximum number of lines in any single revision
+    pub max_lines: Option<usize>,
+    /// Maximum length of any single line, in bytes
+    pub max_line_length: Option<usize>,
+    /// Maximum time a single diff step may take
+    pub diff_deadline: Option<Duration>,
+    /// Maximum time the whole blame may spend diffing
+    pub total_deadline: Option<Duration>,
+    /// What to do when a diff step runs past `diff_deadline` or `total_deadline`
+    pub deadline_fallback: DeadlineFallback,
+    /// Reject revisions that look binary (a NUL byte within the first 8000 bytes, like git)
+    pub reject_binary: bool,
+}
+
+/// Progress of a running blame, reported after each revision is processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of revisions processed so far
+    pub completed: usize,
+    /// Total number of revisions
+    pub total: usize,
+}
+
+impl Progress {
+    /// Fraction of revisions processed, from 0.0 to 1.0
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f64 / self.total as f64
+        }
+    }
+}
+
+/// What to do when a diff step runs past a deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeadlineFallback {
+    /// Fail with [`BlameError::DeadlineExceeded`] (default)
+    #[default]
+    Error,
+    /// Keep the coarser diff the algorithm produced when the deadline hit
+    Coarse,
+    /// Treat the step as a full replacement, attributing every line to the new revision
+    Replace,
+}
+
+/// The diff invariant broken by a [`BlameError::DiffInvariantViolated`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DiffInvariant {
+    /// An unchanged run pointed past the end of the old revision
+    #[error("old index {old_index} out of bounds (len {len})")]
+    OldIndexOutOfBounds { old_index: usize, len: usize },
 }
 
 /// Errors
@@ -77,7 +708,91 @@ pub enum BlameError {
     #[error("no revisions provided")]
     EmptyRevisions,
 
-    /// Invalid input data
-    #[error("invalid input: {0}")]
-    InvalidInput(String),
+    /// Diffing revision `revision_index` against its predecessor broke an invariant
+    #[error("diff invariant violated at revision {revision_index}: {cause}")]
+    DiffInvariantViolated {
+        revision_index: usize,
+        cause: DiffInvariant,
+    },
+
+    /// A requested line is past the end of the final revision
+    #[error("line {line_number} out of range (final revision has {lines} lines)")]
+    LineOutOfRange { line_number: usize, lines: usize },
+
+    /// A requested revision is past the end of the history
+    #[error("revision index {revision_index} out of range ({revisions} revisions)")]
+    RevisionOutOfRange {
+        revision_index: usize,
+        revisions: usize,
+    },
+
+    /// The content of revision `revision_index` is not valid UTF-8
+    #[error("revision {revision_index} is not valid UTF-8 after byte {valid_up_to}")]
+    InvalidUtf8 {
+        revision_index: usize,
+        valid_up_to: usize,
+    },
+
+    /// More revisions were provided than [`BlameLimits::max_revisions`] allows;
+    /// `revision_index` is the first revision past the limit
+    #[error("too many revisions: more than {limit}")]
+    TooManyRevisions { revision_index: usize, limit: usize },
+
+    /// A revision has more lines than [`BlameLimits::max_lines`] allows
+    #[error("revision {revision_index} has {lines} lines, more than the limit of {limit}")]
+    TooManyLines {
+        revision_index: usize,
+        lines: usize,
+        limit: usize,
+    },
+
+    /// A line is longer than [`BlameLimits::max_line_length`] allows
+    #[error(
+        "line {line_number} of revision {revision_index} is {length} bytes long, more than the limit of {limit}"
+    )]
+    LineTooLong {
+        revision_index: usize,
+        line_number: usize,
+        length: usize,
+        limit: usize,
+    },
+
+    /// A revision looks like binary content and [`BlameLimits::reject_binary`] is set
+    #[error("revision {revision_index} looks like binary content")]
+    BinaryContent { revision_index: usize },
+
+    /// Diffing a revision against its predecessor ran past [`BlameLimits::diff_deadline`]
+    /// or [`BlameLimits::total_deadline`]
+    #[error("diffing revision {revision_index} exceeded the deadline of {deadline:?}")]
+    DeadlineExceeded {
+        revision_index: usize,
+        deadline: Duration,
+    },
+
+    /// The progress callback asked to stop before revision `revision_index` was processed
+    #[error("blame cancelled before revision {revision_index}")]
+    Cancelled { revision_index: usize },
+
+    /// Reading history from a git repository failed
+    #[cfg(feature = "git")]
+    #[error("git: {0}")]
+    Git(String),
+}
+
+impl BlameError {
+    /// Index of the revision the error is about, if it concerns a single revision
+    pub fn revision_index(&self) -> Option<usize> {
+        match *self {
+            BlameError::DiffInvariantViolated { revision_index, .. }
+            | BlameError::RevisionOutOfRange { revision_index, .. }
+            | BlameError::InvalidUtf8 { revision_index, .. }
+            | BlameError::TooManyRevisions { revision_index, .. }
+            | BlameError::TooManyLines { revision_index, .. }
+            | BlameError::LineTooLong { revision_index, .. }
+            | BlameError::BinaryContent { revision_index }
+            | BlameError::DeadlineExceeded { revision_index, .. }
+            | BlameError::Cancelled { revision_index } => Some(revision_index),
+            _ => None,
+        }
+    }
 }
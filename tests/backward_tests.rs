@@ -0,0 +1,331 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameLimits, BlameOptions, BlameResult, BlameRevision, BlameStrategy, ByteRevision,
+    Progress, Tokenizer, blame_bytes, blame_chars, blame_with_options, blame_with_progress,
+    blame_with_tokenizer, blame_words, line_histories,
+};
+use common::{history, indexed_revisions, load_fixture, run_with_all_algorithms};
+use std::cell::Cell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+fn backward(options: BlameOptions) -> BlameOptions {
+    BlameOptions {
+        strategy: BlameStrategy::Backward,
+        ..options
+    }
+}
+
+type Attribution<'a> = (&'a str, usize, usize, Option<(usize, usize, &'a str)>);
+
+/// Everything a line's attribution is made of, including its previous line
+fn attribution<'a>(result: &BlameResult<'a, usize>) -> Vec<Attribution<'a>> {
+    result
+        .iter()
+        .map(|line| {
+            (
+                line.content,
+                line.revision_index,
+                *line.revision_metadata,
+                line.previous.as_ref().map(|previous| {
+                    (
+                        previous.revision_index,
+                        previous.line_number,
+                        previous.content,
+                    )
+                }),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_matches_forward_on_fixtures() {
+    for fixture in ["line_modification", "multiple_revisions", "simple_add"] {
+        let contents = load_fixture(fixture);
+        let revisions = indexed_revisions(&contents);
+
+        run_with_all_algorithms(|algorithm| {
+            let options = BlameOptions {
+                algorithm,
+                ..Default::default()
+            };
+            let forward = blame_with_options(&revisions, options).unwrap();
+            let result = blame_with_options(&revisions, backward(options)).unwrap();
+
+            assert_eq!(attribution(&result), attribution(&forward), "{}", fixture);
+            assert_eq!(result.revision_count(), forward.revision_count());
+        });
+    }
+}
+
+#[test]
+fn test_matches_forward_on_generated_histories() {
+    for seed in 0..8 {
+        let contents = history(seed, 40);
+        let revisions = indexed_revisions(&contents);
+
+        run_with_all_algorithms(|algorithm| {
+            for similarity_threshold in [None, Some(50)] {
+                let options = BlameOptions {
+                    algorithm,
+                    similarity_threshold,
+                    ..Default::default()
+                };
+                let forward = blame_with_options(&revisions, options).unwrap();
+                let result = blame_with_options(&revisions, backward(options)).unwrap();
+
+                assert_eq!(
+                    attribution(&result),
+                    attribution(&forward),
+                    "seed {} with {:?}, {:?}",
+                    seed,
+                    algorithm,
+                    similarity_threshold
+                );
+            }
+        });
+    }
+}
+
+#[test]
+fn test_line_histories_match_forward() {
+    let contents = history(3, 30);
+    let revisions = indexed_revisions(&contents);
+    let options = BlameOptions {
+        similarity_threshold: Some(50),
+        ..Default::default()
+    };
+
+    let forward = line_histories(&revisions, options).unwrap();
+    let result = line_histories(&revisions, backward(options)).unwrap();
+
+    assert_eq!(result.len(), forward.len());
+    assert!(forward.iter().any(|history| history.len() > 1));
+    for (a, b) in result.iter().zip(&forward) {
+        let a: Vec<_> = a
+            .iter()
+            .map(|entry| (entry.revision_index, entry.line_number, entry.content))
+            .collect();
+        let b: Vec<_> = b
+            .iter()
+            .map(|entry| (entry.revision_index, entry.line_number, entry.content))
+            .collect();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_stops_once_every_line_is_attributed() {
+    struct CountingTokenizer<'c>(&'c Cell<usize>);
+
+    impl Tokenizer for CountingTokenizer<'_> {
+        fn tokenize<'a>(&self, content: &'a str) -> Vec<&'a str> {
+            self.0.set(self.0.get() + 1);
+            content.split_inclusive('\n').collect()
+        }
+    }
+
+    // Revision 8 replaces everything, so revisions 0 to 6 can't contribute a line
+    let mut contents: Vec<String> = (0..8).map(|i| format!("old {}\n", i)).collect();
+    contents.push("new\n".to_string());
+    contents.push("new\nnewer\n".to_string());
+    let revisions = indexed_revisions(&contents);
+
+    let tokenized = Cell::new(0);
+    let tokenizer = CountingTokenizer(&tokenized);
+    let result =
+        blame_with_tokenizer(&revisions, backward(BlameOptions::default()), &tokenizer).unwrap();
+    let attribution: Vec<usize> = result.iter().map(|line| line.revision_index).collect();
+    assert_eq!(attribution, vec![8, 9]);
+    assert_eq!(result.revision_count(), 10);
+    assert_eq!(tokenized.get(), 3);
+
+    tokenized.set(0);
+    blame_with_tokenizer(&revisions, BlameOptions::default(), &tokenizer).unwrap();
+    assert_eq!(tokenized.get(), 10);
+
+    let mut reported = Vec::new();
+    blame_with_progress(&revisions, backward(BlameOptions::default()), |progress| {
+        reported.push(progress);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    let completed: Vec<usize> = reported.iter().map(|progress| progress.completed).collect();
+    assert_eq!(completed, vec![1, 2, 3, 10]);
+    assert_eq!(
+        reported.last(),
+        Some(&Progress {
+            completed: 10,
+            total: 10
+        })
+    );
+}
+
+#[test]
+fn test_cancellation_names_the_next_older_revision() {
+    let contents = history(1, 10);
+    let revisions = indexed_revisions(&contents);
+
+    let mut calls = 0;
+    let result = blame_with_progress(&revisions, backward(BlameOptions::default()), |_| {
+        calls += 1;
+        if calls == 2 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert!(matches!(
+        result,
+        Err(BlameError::Cancelled { revision_index: 7 })
+    ));
+}
+
+#[test]
+fn test_limits_skip_unreached_revisions() {
+    let contents: Vec<String> = vec![
+        "\0binary\n".into(),
+        "text\n".into(),
+        "other\n".into(),
+        "other\nmore\n".into(),
+    ];
+    let revisions = indexed_revisions(&contents);
+    let options = BlameOptions {
+        limits: BlameLimits {
+            reject_binary: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::BinaryContent { revision_index: 0 })
+    ));
+    let result = blame_with_options(&revisions, backward(options)).unwrap();
+    let attribution: Vec<usize> = result.iter().map(|line| line.revision_index).collect();
+    assert_eq!(attribution, vec![2, 3]);
+}
+
+#[test]
+fn test_reports_match_forward() {
+    let contents: Vec<String> = ["a\n", "a\nb\n", "a\nb\n", "", "c\n", "a\nc\n", "c\n"]
+        .iter()
+        .map(|content| content.to_string())
+        .collect();
+    let mut revisions = indexed_revisions(&contents);
+    // Two different contents sharing one metadata `Rc`
+    revisions[5].metadata = Rc::clone(&revisions[4].metadata);
+
+    for (validate, report_empty_revisions) in [(true, false), (false, true), (true, true)] {
+        let options = BlameOptions {
+            validate,
+            report_empty_revisions,
+            ..Default::default()
+        };
+        let forward = blame_with_options(&revisions, options).unwrap();
+        let result = blame_with_options(&revisions, backward(options)).unwrap();
+
+        assert_eq!(attribution(&result), attribution(&forward));
+        assert_eq!(result.warnings(), forward.warnings());
+        assert_eq!(result.empty_revisions(), forward.empty_revisions());
+        assert_eq!(
+            result.revisions_without_lines(),
+            forward.revisions_without_lines()
+        );
+    }
+}
+
+#[test]
+fn test_word_and_char_blame_match_forward() {
+    let contents = history(5, 20);
+    let revisions = indexed_revisions(&contents);
+    let options = BlameOptions::default();
+
+    let forward = blame_words(&revisions, options).unwrap();
+    let result = blame_words(&revisions, backward(options)).unwrap();
+    assert_eq!(result.len(), forward.len());
+    for (a, b) in result.iter().zip(forward.iter()) {
+        let a: Vec<_> = a
+            .spans
+            .iter()
+            .map(|span| (span.range.clone(), span.revision_index))
+            .collect();
+        let b: Vec<_> = b
+            .spans
+            .iter()
+            .map(|span| (span.range.clone(), span.revision_index))
+            .collect();
+        assert_eq!(a, b);
+    }
+
+    let forward = blame_chars(&revisions[..8], options).unwrap();
+    let result = blame_chars(&revisions[..8], backward(options)).unwrap();
+    let a: Vec<_> = result
+        .iter()
+        .map(|span| (span.range.clone(), span.revision_index))
+        .collect();
+    let b: Vec<_> = forward
+        .iter()
+        .map(|span| (span.range.clone(), span.revision_index))
+        .collect();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_bytes_match_forward() {
+    let contents = history(7, 25);
+    let revisions: Vec<ByteRevision<usize>> = contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| ByteRevision {
+            content: content.as_bytes(),
+            metadata: Rc::new(idx),
+        })
+        .collect();
+    let options = BlameOptions {
+        similarity_threshold: Some(50),
+        ..Default::default()
+    };
+
+    let forward = blame_bytes(&revisions, options).unwrap();
+    let result = blame_bytes(&revisions, backward(options)).unwrap();
+    let to_tuples = |result: &blame_rs::ByteBlameResult<usize>| -> Vec<_> {
+        result
+            .iter()
+            .map(|line| {
+                (
+                    line.content.to_vec(),
+                    line.revision_index,
+                    line.previous
+                        .as_ref()
+                        .map(|previous| (previous.revision_index, previous.line_number)),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(to_tuples(&result), to_tuples(&forward));
+}
+
+#[test]
+fn test_single_and_empty_revisions() {
+    let single = vec!["x\ny".to_string()];
+    let single = indexed_revisions(&single);
+    let result = blame_with_options(&single, backward(BlameOptions::default())).unwrap();
+    assert_eq!(result.len(), 2);
+    assert!(result.iter().all(|line| line.revision_index == 0));
+
+    let emptied = vec!["a\n".to_string(), String::new()];
+    let emptied = indexed_revisions(&emptied);
+    let result = blame_with_options(&emptied, backward(BlameOptions::default())).unwrap();
+    assert!(result.is_empty());
+    assert_eq!(result.revision_count(), 2);
+
+    let empty: Vec<BlameRevision<usize>> = Vec::new();
+    assert!(matches!(
+        blame_with_options(&empty, backward(BlameOptions::default())),
+        Err(BlameError::EmptyRevisions)
+    ));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameOptions, BlameRevision, ByteBlameResult, ByteRevision, DiffAlgorithm,
+    blame_bytes, blame_with_options,
+};
+use common::{TestMetadata, run_with_all_algorithms};
+use std::rc::Rc;
+
+fn revisions<'a>(contents: &[&'a [u8]]) -> Vec<ByteRevision<'a, TestMetadata>> {
+    contents
+        .iter()
+        .enumerate()
+        .map(|(idx, &content)| ByteRevision {
+            content,
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect()
+}
+
+fn options(algorithm: DiffAlgorithm) -> BlameOptions {
+    BlameOptions {
+        algorithm,
+        ..Default::default()
+    }
+}
+
+fn attribution<'a>(result: &ByteBlameResult<'a, TestMetadata>) -> Vec<(&'a [u8], usize)> {
+    result
+        .iter()
+        .map(|line| (line.content, line.revision_metadata.revision))
+        .collect()
+}
+
+#[test]
+fn test_latin1_content() {
+    // "café" and "naïve" encoded as Latin-1, which is invalid UTF-8
+    let revisions = revisions(&[b"caf\xe9\nna\xefve\n", b"caf\xe9\nna\xefve\nfin\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_bytes(&revisions, options(algorithm)).unwrap();
+        assert_eq!(
+            attribution(&result),
+            vec![
+                (&b"caf\xe9\n"[..], 0),
+                (&b"na\xefve\n"[..], 0),
+                (&b"fin\n"[..], 1),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_invalid_utf8_lines_are_tracked() {
+    let revisions = revisions(&[
+        b"\xff\xfe header\nbody\n",
+        b"\xff\xfe header\n\x80 inserted\nbody\n",
+        b"\x80 inserted\nbody\n\xc3\x28 tail",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_bytes(&revisions, options(algorithm)).unwrap();
+        assert_eq!(
+            attribution(&result),
+            vec![
+                (&b"\x80 inserted\n"[..], 1),
+                (&b"body\n"[..], 0),
+                (&b"\xc3\x28 tail"[..], 2),
+            ]
+        );
+
+        let reconstructed: Vec<u8> = result
+            .iter()
+            .flat_map(|line| line.content)
+            .copied()
+            .collect();
+        assert_eq!(reconstructed, revisions[2].content);
+    });
+}
+
+#[test]
+fn test_matches_string_blame_on_utf8() {
+    let contents = ["a\r\nb\n", "a\r\nb\nc", "z\na\r\nc"];
+    let text_revisions: Vec<BlameRevision<TestMetadata>> = contents
+        .iter()
+        .enumerate()
+        .map(|(idx, &content)| BlameRevision {
+            content,
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect();
+    let byte_contents: Vec<&[u8]> = contents.iter().map(|content| content.as_bytes()).collect();
+    let byte_revisions = revisions(&byte_contents);
+
+    run_with_all_algorithms(|algorithm| {
+        let text = blame_with_options(&text_revisions, options(algorithm)).unwrap();
+        let bytes = blame_bytes(&byte_revisions, options(algorithm)).unwrap();
+
+        assert_eq!(text.len(), bytes.len());
+        for (a, b) in text.iter().zip(bytes.iter()) {
+            assert_eq!(a.content.as_bytes(), b.content);
+            assert_eq!(a.revision_index, b.revision_index);
+        }
+    });
+}
+
+#[test]
+fn test_previous_line_pairing_on_bytes() {
+    let revisions = revisions(&[b"value = \xe9t\xe9\n", b"value = \xe9t\xe9!\n"]);
+    let options = BlameOptions {
+        similarity_threshold: Some(80),
+        ..Default::default()
+    };
+
+    let result = blame_bytes(&revisions, options).unwrap();
+    let line = result.get_line(0).unwrap();
+    assert_eq!(line.revision_index, 1);
+    assert_eq!(
+        line.previous.as_ref().unwrap().content,
+        b"value = \xe9t\xe9\n"
+    );
+}
+
+#[test]
+fn test_empty_content() {
+    let revisions = revisions(&[b"a\n", b""]);
+    let result = blame_bytes(&revisions, BlameOptions::default()).unwrap();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_empty_revisions() {
+    let revisions: Vec<ByteRevision<TestMetadata>> = Vec::new();
+    assert!(matches!(
+        blame_bytes(&revisions, BlameOptions::default()),
+        Err(BlameError::EmptyRevisions)
+    ));
+}
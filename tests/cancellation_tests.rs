@@ -0,0 +1,110 @@
+mod common;
+
+use blame_rs::{BlameError, BlameOptions, Progress, blame_with_options, blame_with_progress};
+use common::{revisions, run_with_all_algorithms};
+use std::cell::Cell;
+use std::ops::ControlFlow;
+
+const HISTORY: [&str; 4] = ["a\n", "a\nb\n", "a\nb\nc\n", "b\nc\nd\n"];
+
+#[test]
+fn test_progress_reports_every_revision() {
+    let revisions = revisions(&HISTORY);
+    let mut reported = Vec::new();
+
+    blame_with_progress(&revisions, BlameOptions::default(), |progress| {
+        reported.push(progress);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+
+    let expected: Vec<Progress> = (1..=4)
+        .map(|completed| Progress {
+            completed,
+            total: 4,
+        })
+        .collect();
+    assert_eq!(reported, expected);
+    assert_eq!(reported.last().unwrap().fraction(), 1.0);
+}
+
+#[test]
+fn test_progress_matches_blame_with_options() {
+    let revisions = revisions(&HISTORY);
+
+    run_with_all_algorithms(|algorithm| {
+        let options = BlameOptions {
+            algorithm,
+            ..Default::default()
+        };
+        let expected = blame_with_options(&revisions, options).unwrap();
+        let result =
+            blame_with_progress(&revisions, options, |_| ControlFlow::Continue(())).unwrap();
+
+        assert_eq!(result.len(), expected.len());
+        for (a, b) in result.iter().zip(expected.iter()) {
+            assert_eq!(a.content, b.content);
+            assert_eq!(a.revision_metadata.revision, b.revision_metadata.revision);
+        }
+    });
+}
+
+#[test]
+fn test_cancel_between_revisions() {
+    let revisions = revisions(&HISTORY);
+    let calls = Cell::new(0);
+
+    let result = blame_with_progress(&revisions, BlameOptions::default(), |progress| {
+        calls.set(calls.get() + 1);
+        if progress.completed == 2 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+
+    assert!(matches!(
+        result,
+        Err(BlameError::Cancelled { revision_index: 2 })
+    ));
+    // No further revision is processed once cancelled
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn test_cancel_before_first_diff() {
+    let revisions = revisions(&HISTORY);
+    let result = blame_with_progress(&revisions, BlameOptions::default(), |_| {
+        ControlFlow::Break(())
+    });
+
+    assert!(matches!(
+        result,
+        Err(BlameError::Cancelled { revision_index: 1 })
+    ));
+}
+
+#[test]
+fn test_break_after_last_revision_is_ignored() {
+    let revisions = revisions(&HISTORY);
+    let result = blame_with_progress(&revisions, BlameOptions::default(), |progress| {
+        if progress.completed == progress.total {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+
+    assert_eq!(result.len(), 3);
+}
+
+#[test]
+fn test_empty_revisions() {
+    let revisions = revisions(&[]);
+    let result = blame_with_progress(&revisions, BlameOptions::default(), |_| {
+        ControlFlow::Continue(())
+    });
+
+    assert!(matches!(result, Err(BlameError::EmptyRevisions)));
+}
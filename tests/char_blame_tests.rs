@@ -0,0 +1,115 @@
+mod common;
+
+use blame_rs::{BlameOptions, BlameRevision, CharBlameResult, blame_chars};
+use common::run_with_all_algorithms;
+use std::rc::Rc;
+
+#[derive(Debug)]
+struct TestMetadata {
+    author: &'static str,
+}
+
+fn revisions<'a>(contents: &[(&'a str, &'static str)]) -> Vec<BlameRevision<'a, TestMetadata>> {
+    contents
+        .iter()
+        .map(|&(content, author)| BlameRevision {
+            content,
+            metadata: Rc::new(TestMetadata { author }),
+        })
+        .collect()
+}
+
+fn spans_of<'a>(result: &CharBlameResult<'a, TestMetadata>) -> Vec<(&'a str, &'static str)> {
+    result
+        .iter()
+        .map(|span| (span.content, span.revision_metadata.author))
+        .collect()
+}
+
+#[test]
+fn test_collaborative_edits_are_run_length_encoded() {
+    let revisions = revisions(&[
+        ("Hello world", "Alice"),
+        ("Hello, world", "Bob"),
+        ("Hello, World!", "Carol"),
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_chars(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("blame_chars");
+
+        assert_eq!(result.content(), "Hello, World!");
+        assert_eq!(
+            spans_of(&result),
+            vec![
+                ("Hello", "Alice"),
+                (",", "Bob"),
+                (" ", "Alice"),
+                ("W", "Carol"),
+                ("orld", "Alice"),
+                ("!", "Carol"),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_multibyte_boundaries_are_preserved() {
+    let revisions = revisions(&[("naïve café", "Alice"), ("naïve 日本 café ☕", "Bob")]);
+    let content = revisions[1].content;
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_chars(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("blame_chars");
+
+        let mut offset = 0;
+        for span in result.iter() {
+            assert_eq!(span.range.start, offset);
+            assert!(content.is_char_boundary(span.range.start));
+            assert!(content.is_char_boundary(span.range.end));
+            assert_eq!(&content[span.range.clone()], span.content);
+            offset = span.range.end;
+        }
+        assert_eq!(offset, content.len());
+
+        let cup = content.find('☕').expect("cup");
+        assert_eq!(
+            result.span_at(cup).expect("span").revision_metadata.author,
+            "Bob"
+        );
+        assert_eq!(
+            result.span_at(2).expect("span").revision_metadata.author,
+            "Alice"
+        );
+        assert!(result.span_at(content.len()).is_none());
+    });
+}
+
+#[test]
+fn test_newlines_are_tracked_like_other_characters() {
+    let revisions = revisions(&[("a\nb", "Alice"), ("a\nb\n", "Bob")]);
+
+    let result = blame_chars(&revisions, BlameOptions::default()).expect("blame_chars");
+    assert_eq!(spans_of(&result), vec![("a\nb", "Alice"), ("\n", "Bob")]);
+}
+
+#[test]
+fn test_empty_final_revision_has_no_spans() {
+    let revisions = revisions(&[("text", "Alice"), ("", "Bob")]);
+
+    let result = blame_chars(&revisions, BlameOptions::default()).expect("blame_chars");
+    assert!(result.is_empty());
+    assert!(result.span_at(0).is_none());
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_blame-rs"))
+        .args(args)
+        .output()
+        .expect("Failed to run blame-rs binary")
+}
+
+fn stdout_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_cli_blames_listed_files() {
+    let output = run_cli(&[
+        "tests/fixtures/simple_add/rev0.txt",
+        "tests/fixtures/simple_add/rev1.txt",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec![
+            "rev0.txt 1) line 1",
+            "rev0.txt 2) line 2",
+            "rev1.txt 3) line 3"
+        ]
+    );
+}
+
+#[test]
+fn test_cli_orders_directory_numerically() {
+    let output = run_cli(&["tests/fixtures/multiple_revisions", "-a", "patience"]);
+
+    assert!(output.status.success());
+    let lines = stdout_lines(&output);
+    assert_eq!(lines.len(), 4);
+    assert!(lines[2].starts_with("rev1.txt"));
+    assert!(lines[3].starts_with("rev2.txt"));
+}
+
+#[test]
+fn test_cli_line_range_and_html_format() {
+    let output = run_cli(&[
+        "tests/fixtures/multiple_revisions",
+        "-L",
+        "2,3",
+        "--format",
+        "html",
+    ]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("<tr>").count(), 2);
+    assert!(stdout.contains("<td class=\"line-number\">2</td>"));
+    assert!(stdout.contains("<td class=\"line-number\">3</td>"));
+}
+
+#[test]
+fn test_cli_rejects_out_of_range_lines() {
+    let output = run_cli(&["tests/fixtures/simple_add", "-L", "10,"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only 3 lines"));
+}
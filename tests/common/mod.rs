@@ -0,0 +1,195 @@
+#![allow(dead_code)]
+
+use blame_rs::{BlameRevision, DiffAlgorithm};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::rc::Rc;
+use tempfile::TempDir;
+
+#[derive(Debug, PartialEq)]
+pub struct TestMetadata {
+    pub revision: usize,
+}
+
+/// Revisions over `contents`, each tagged with its index
+pub fn revisions<'a>(contents: &[&'a str]) -> Vec<BlameRevision<'a, TestMetadata>> {
+    contents
+        .iter()
+        .enumerate()
+        .map(|(idx, &content)| BlameRevision {
+            content,
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect()
+}
+
+/// Revisions over owned `contents`, with the index itself as metadata
+pub fn indexed_revisions(contents: &[String]) -> Vec<BlameRevision<'_, usize>> {
+    contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| BlameRevision {
+            content,
+            metadata: Rc::new(idx),
+        })
+        .collect()
+}
+
+pub fn run_with_all_algorithms<F>(mut f: F)
+where
+    F: FnMut(DiffAlgorithm),
+{
+    f(DiffAlgorithm::Myers);
+    f(DiffAlgorithm::Patience);
+}
+
+/// Reads `rev0.txt`, `rev1.txt`, ... from a fixture directory until one is missing
+pub fn load_revisions(fixture_path: &Path) -> Vec<String> {
+    let mut contents = Vec::new();
+    loop {
+        let rev_file = fixture_path.join(format!("rev{}.txt", contents.len()));
+        if !rev_file.exists() {
+            break;
+        }
+        contents.push(
+            fs::read_to_string(&rev_file)
+                .unwrap_or_else(|_| panic!("Failed to read {:?}", rev_file)),
+        );
+    }
+    contents
+}
+
+/// The revisions of fixture `fixture_dir` under `tests/fixtures`
+pub fn load_fixture(fixture_dir: &str) -> Vec<String> {
+    load_revisions(&Path::new("tests/fixtures").join(fixture_dir))
+}
+
+/// Deterministic history of small edits over a file with many repeated lines
+pub fn history(seed: u64, revisions: usize) -> Vec<String> {
+    let mut state = seed;
+    let mut next = move |bound: usize| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as usize % bound
+    };
+
+    let mut lines: Vec<String> = (0..120).map(|i| format!("line {}\n", i % 30)).collect();
+    let mut contents = vec![lines.concat()];
+    for revision in 1..revisions {
+        for _ in 0..=next(4) {
+            let at = next(lines.len() + 1);
+            match next(4) {
+                0 => lines.insert(at, format!("added {}\n", revision)),
+                1 if at < lines.len() => {
+                    lines.remove(at);
+                }
+                // Small edits, so similarity pairing has something to link
+                2 if at < lines.len() => lines[at] = format!("{}!\n", lines[at].trim_end()),
+                _ if at < lines.len() => lines[at] = format!("edited {}\n", next(10)),
+                _ => lines.push(format!("appended {}\n", revision)),
+            }
+        }
+        contents.push(lines.concat());
+    }
+    contents
+}
+
+/// A throwaway git repository with deterministic author and commit dates
+pub struct GitRepo {
+    dir: TempDir,
+    commits: usize,
+}
+
+impl GitRepo {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let repo = Self { dir, commits: 0 };
+        repo.git(&["init", "--quiet", "--initial-branch=main"]);
+        repo
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn git(&self, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(self.path())
+            .args(args)
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .output()
+            .expect("Failed to run git");
+
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        String::from_utf8(output.stdout).expect("git output should be UTF-8")
+    }
+
+    pub fn write(&self, file: &str, content: &str) {
+        let path = self.path().join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent dir");
+        }
+        fs::write(path, content).expect("Failed to write file");
+    }
+
+    pub fn rename(&self, from: &str, to: &str) {
+        if let Some(parent) = self.path().join(to).parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent dir");
+        }
+        self.git(&["mv", from, to]);
+    }
+
+    /// Stages everything and commits, returning the new commit hash
+    pub fn commit(&mut self, author: &str, message: &str) -> String {
+        // One hour apart so commit order is unambiguous
+        let date = format!("{} +0000", 1_700_000_000 + self.commits * 3600);
+        self.commits += 1;
+
+        self.git(&["add", "--all"]);
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(self.path())
+            .args(["commit", "--quiet", "--allow-empty", "-m", message])
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_AUTHOR_NAME", author)
+            .env(
+                "GIT_AUTHOR_EMAIL",
+                format!("{}@example.com", author.to_lowercase()),
+            )
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_NAME", author)
+            .env(
+                "GIT_COMMITTER_EMAIL",
+                format!("{}@example.com", author.to_lowercase()),
+            )
+            .env("GIT_COMMITTER_DATE", &date)
+            .output()
+            .expect("Failed to run git commit");
+
+        assert!(
+            output.status.success(),
+            "git commit failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        self.git(&["rev-parse", "HEAD"]).trim().to_string()
+    }
+}
+
+pub fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
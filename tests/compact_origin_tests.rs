@@ -0,0 +1,135 @@
+mod common;
+
+use blame_rs::{BlameOptions, DiffAlgorithm, blame_snapshots, blame_with_options, line_histories};
+use common::{history, indexed_revisions};
+use similar::DiffTag;
+use similar::algorithms::{Algorithm, Capture, diff_slices};
+use std::rc::Rc;
+
+/// Reference blame keeping one `(revision, line_number)` per line, diffing whole revisions
+fn reference_blame(contents: &[String], algorithm: Algorithm) -> Vec<(usize, usize)> {
+    let mut old: Vec<&str> = contents[0].split_inclusive('\n').collect();
+    let mut origins: Vec<(usize, usize)> = (0..old.len()).map(|line| (0, line)).collect();
+
+    for (revision, content) in contents.iter().enumerate().skip(1) {
+        let new: Vec<&str> = content.split_inclusive('\n').collect();
+        let mut capture = Capture::new();
+        diff_slices(algorithm, &mut capture, &old, &new).unwrap();
+
+        let mut new_origins = Vec::with_capacity(new.len());
+        for op in capture.into_ops() {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                new_origins.extend_from_slice(&origins[old_range]);
+            } else {
+                new_origins.extend(new_range.map(|line| (revision, line)));
+            }
+        }
+        old = new;
+        origins = new_origins;
+    }
+    origins
+}
+
+#[test]
+fn test_matches_per_line_reference() {
+    for seed in 0..8 {
+        let contents = history(seed, 60);
+        let revisions = indexed_revisions(&contents);
+        let expected = reference_blame(&contents, Algorithm::Myers);
+
+        let result = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+        let attribution: Vec<usize> = result.iter().map(|line| line.revision_index).collect();
+        let expected_revisions: Vec<usize> =
+            expected.iter().map(|&(revision, _)| revision).collect();
+        assert_eq!(attribution, expected_revisions, "seed {}", seed);
+
+        let reconstructed: String = result.iter().map(|line| line.content).collect();
+        assert_eq!(reconstructed, contents[59], "seed {}", seed);
+    }
+}
+
+#[test]
+fn test_patience_matches_whole_revision_reference() {
+    // Patience anchors on lines unique to each whole revision, so its results depend on the
+    // unchanged ends too; repeated lines make that visible
+    for seed in 0..40 {
+        let contents = history(seed, 30);
+        let revisions = indexed_revisions(&contents);
+        let expected = reference_blame(&contents, Algorithm::Patience);
+        let options = BlameOptions {
+            algorithm: DiffAlgorithm::Patience,
+            ..Default::default()
+        };
+
+        let result = blame_with_options(&revisions, options).unwrap();
+        let attribution: Vec<usize> = result.iter().map(|line| line.revision_index).collect();
+        let expected_revisions: Vec<usize> =
+            expected.iter().map(|&(revision, _)| revision).collect();
+        assert_eq!(attribution, expected_revisions, "seed {}", seed);
+    }
+}
+
+#[test]
+fn test_line_numbers_in_introducing_revision() {
+    let contents = history(42, 40);
+    let revisions = indexed_revisions(&contents);
+    let expected = reference_blame(&contents, Algorithm::Myers);
+    let options = BlameOptions {
+        // Pairing cuts paired lines out into runs of their own
+        similarity_threshold: Some(60),
+        ..Default::default()
+    };
+
+    let histories = line_histories(&revisions, options).unwrap();
+    assert_eq!(histories.len(), expected.len());
+    for (history, &(revision, line_number)) in histories.iter().zip(&expected) {
+        let newest = &history.entries()[0];
+        assert_eq!(
+            (newest.revision_index, newest.line_number),
+            (revision, line_number)
+        );
+
+        // Every entry's content is the line at that position of its revision
+        for entry in history.iter() {
+            let line = contents[entry.revision_index]
+                .split_inclusive('\n')
+                .nth(entry.line_number);
+            assert_eq!(line, Some(entry.content));
+        }
+    }
+}
+
+#[test]
+fn test_snapshots_match_blame_of_each_prefix() {
+    let contents = history(7, 25);
+    let revisions = indexed_revisions(&contents);
+    let snapshots = blame_snapshots(&revisions, BlameOptions::default()).unwrap();
+
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let expected = blame_with_options(&revisions[..=index], BlameOptions::default()).unwrap();
+        let a: Vec<(&str, usize)> = snapshot
+            .iter()
+            .map(|line| (line.content, line.revision_index))
+            .collect();
+        let b: Vec<(&str, usize)> = expected
+            .iter()
+            .map(|line| (line.content, line.revision_index))
+            .collect();
+        assert_eq!(a, b, "snapshot {}", index);
+    }
+}
+
+#[test]
+fn test_metadata_is_shared_per_revision() {
+    let contents = history(3, 20);
+    let revisions = indexed_revisions(&contents);
+    let result = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+
+    for line in result.iter() {
+        assert!(Rc::ptr_eq(
+            &line.revision_metadata,
+            &revisions[line.revision_index].metadata
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameLimits, BlameOptions, DeadlineFallback, blame_snapshots, blame_with_options,
+    blame_words,
+};
+use common::indexed_revisions;
+use std::time::Duration;
+
+/// A history large enough that no diff step finishes within a zero deadline
+fn history() -> Vec<String> {
+    let base: Vec<String> = (0..3_000).map(|i| format!("line {}\n", i)).collect();
+    let mut edited = base.clone();
+    for i in (0..edited.len()).step_by(7) {
+        edited[i] = format!("edited {}\n", i);
+    }
+    let mut extended = edited.clone();
+    extended.push("appended\n".to_string());
+
+    vec![base.concat(), edited.concat(), extended.concat()]
+}
+
+fn options(limits: BlameLimits) -> BlameOptions {
+    BlameOptions {
+        limits,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_error_is_the_default_fallback() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents);
+    let options = options(BlameLimits {
+        diff_deadline: Some(Duration::ZERO),
+        ..Default::default()
+    });
+
+    assert_eq!(options.limits.deadline_fallback, DeadlineFallback::Error);
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::DeadlineExceeded {
+            revision_index: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_replace_fallback_attributes_everything_to_the_step() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents[..2]);
+    let options = options(BlameLimits {
+        diff_deadline: Some(Duration::ZERO),
+        deadline_fallback: DeadlineFallback::Replace,
+        ..Default::default()
+    });
+
+    let result = blame_with_options(&revisions, options).unwrap();
+    assert_eq!(result.approximated_revisions(), &[1]);
+    assert!(result.iter().all(|line| line.revision_index == 1));
+
+    let reconstructed: String = result.iter().map(|line| line.content).collect();
+    assert_eq!(reconstructed, contents[1]);
+}
+
+#[test]
+fn test_coarse_fallback_keeps_content_exact() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents);
+    let options = options(BlameLimits {
+        diff_deadline: Some(Duration::ZERO),
+        deadline_fallback: DeadlineFallback::Coarse,
+        ..Default::default()
+    });
+
+    let result = blame_with_options(&revisions, options).unwrap();
+    assert_eq!(result.approximated_revisions(), &[1, 2]);
+
+    let reconstructed: String = result.iter().map(|line| line.content).collect();
+    assert_eq!(reconstructed, contents[2]);
+}
+
+#[test]
+fn test_total_deadline() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents);
+
+    let options_for = |deadline_fallback| {
+        options(BlameLimits {
+            total_deadline: Some(Duration::ZERO),
+            deadline_fallback,
+            ..Default::default()
+        })
+    };
+
+    assert!(matches!(
+        blame_with_options(&revisions, options_for(DeadlineFallback::Error)),
+        Err(BlameError::DeadlineExceeded { .. })
+    ));
+
+    let result = blame_with_options(&revisions, options_for(DeadlineFallback::Replace)).unwrap();
+    assert_eq!(result.approximated_revisions(), &[1, 2]);
+    assert!(result.iter().all(|line| line.revision_index == 2));
+}
+
+#[test]
+fn test_generous_deadlines_are_exact() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents);
+    let options = options(BlameLimits {
+        diff_deadline: Some(Duration::from_secs(60)),
+        total_deadline: Some(Duration::from_secs(600)),
+        deadline_fallback: DeadlineFallback::Replace,
+        ..Default::default()
+    });
+
+    let exact = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+    let result = blame_with_options(&revisions, options).unwrap();
+
+    assert!(result.approximated_revisions().is_empty());
+    for (a, b) in result.iter().zip(exact.iter()) {
+        assert_eq!(a.revision_index, b.revision_index);
+    }
+}
+
+#[test]
+fn test_approximations_are_reported_by_other_modes() {
+    let contents = history();
+    let revisions = indexed_revisions(&contents);
+    let options = options(BlameLimits {
+        diff_deadline: Some(Duration::ZERO),
+        deadline_fallback: DeadlineFallback::Replace,
+        ..Default::default()
+    });
+
+    let words = blame_words(&revisions, options).unwrap();
+    assert_eq!(words.approximated_revisions(), &[1, 2]);
+
+    let snapshots = blame_snapshots(&revisions, options).unwrap();
+    let approximated: Vec<&[usize]> = snapshots
+        .iter()
+        .map(|snapshot| snapshot.approximated_revisions())
+        .collect();
+    assert_eq!(approximated, vec![&[][..], &[1][..], &[1, 2][..]]);
+}
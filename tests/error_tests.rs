@@ -0,0 +1,109 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameLimits, BlameOptions, DiffInvariant, blame_snapshots_at, blame_with_options,
+    blame_with_progress, line_history,
+};
+use common::revisions;
+use std::ops::ControlFlow;
+
+const HISTORY: [&str; 3] = ["a\n", "a\nb\n", "a\nb\n\0\n"];
+
+#[test]
+fn test_limit_errors_name_the_revision() {
+    let revisions = revisions(&HISTORY);
+
+    let options = BlameOptions {
+        limits: BlameLimits {
+            reject_binary: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = blame_with_options(&revisions, options).unwrap_err();
+    assert_eq!(err.revision_index(), Some(2));
+
+    let options = BlameOptions {
+        limits: BlameLimits {
+            max_lines: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = blame_with_options(&revisions, options).unwrap_err();
+    assert_eq!(err.revision_index(), Some(1));
+
+    let options = BlameOptions {
+        limits: BlameLimits {
+            max_revisions: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = blame_with_options(&revisions, options).unwrap_err();
+    assert_eq!(err.revision_index(), Some(2));
+}
+
+#[test]
+fn test_range_errors() {
+    let revisions = revisions(&HISTORY);
+
+    let err = blame_snapshots_at(&revisions, BlameOptions::default(), &[0, 7]).unwrap_err();
+    assert!(matches!(
+        err,
+        BlameError::RevisionOutOfRange {
+            revision_index: 7,
+            revisions: 3,
+        }
+    ));
+    assert_eq!(err.revision_index(), Some(7));
+
+    let err = line_history(&revisions, BlameOptions::default(), 4).unwrap_err();
+    assert!(matches!(
+        err,
+        BlameError::LineOutOfRange {
+            line_number: 4,
+            lines: 3,
+        }
+    ));
+    // A line range error is about the final revision as a whole
+    assert_eq!(err.revision_index(), None);
+}
+
+#[test]
+fn test_cancelled_names_the_revision() {
+    let revisions = revisions(&HISTORY);
+    let err = blame_with_progress(&revisions, BlameOptions::default(), |_| {
+        ControlFlow::Break(())
+    })
+    .unwrap_err();
+
+    assert_eq!(err.revision_index(), Some(1));
+}
+
+#[test]
+fn test_error_messages() {
+    let err = BlameError::DiffInvariantViolated {
+        revision_index: 3,
+        cause: DiffInvariant::OldIndexOutOfBounds {
+            old_index: 9,
+            len: 4,
+        },
+    };
+    assert_eq!(
+        err.to_string(),
+        "diff invariant violated at revision 3: old index 9 out of bounds (len 4)"
+    );
+    assert_eq!(err.revision_index(), Some(3));
+
+    let err = BlameError::InvalidUtf8 {
+        revision_index: 2,
+        valid_up_to: 17,
+    };
+    assert_eq!(
+        err.to_string(),
+        "revision 2 is not valid UTF-8 after byte 17"
+    );
+
+    assert_eq!(BlameError::EmptyRevisions.revision_index(), None);
+}
@@ -1,14 +1,12 @@
+mod common;
+
 use blame_rs::{BlameError, BlameOptions, BlameRevision, DiffAlgorithm, blame, blame_with_options};
+use common::{TestMetadata, load_revisions, revisions, run_with_all_algorithms};
 use serde::Deserialize;
 use std::fs;
 use std::path::Path;
 use std::rc::Rc;
 
-#[derive(Debug, PartialEq)]
-struct TestMetadata {
-    revision: usize,
-}
-
 #[derive(Deserialize)]
 struct ExpectedLine {
     line: usize,
@@ -27,30 +25,10 @@ fn run_fixture_test(fixture_dir: &str, algorithm: DiffAlgorithm) {
 
     let fixture_path = Path::new("tests/fixtures").join(fixture_dir);
 
-    let mut contents = Vec::new();
-    let mut rev_idx = 0;
-
-    loop {
-        let rev_file = fixture_path.join(format!("rev{}.txt", rev_idx));
-        if !rev_file.exists() {
-            break;
-        }
-
-        let content = fs::read_to_string(&rev_file)
-            .unwrap_or_else(|_| panic!("Failed to read {:?}", rev_file));
-        contents.push(content);
-
-        rev_idx += 1;
-    }
+    let contents = load_revisions(&fixture_path);
 
-    let revisions: Vec<BlameRevision<TestMetadata>> = contents
-        .iter()
-        .enumerate()
-        .map(|(idx, content)| BlameRevision {
-            content: content.as_str(),
-            metadata: Rc::new(TestMetadata { revision: idx }),
-        })
-        .collect();
+    let texts: Vec<&str> = contents.iter().map(String::as_str).collect();
+    let revisions = revisions(&texts);
 
     assert!(
         !revisions.is_empty(),
@@ -69,7 +47,10 @@ fn run_fixture_test(fixture_dir: &str, algorithm: DiffAlgorithm) {
     let expected: Vec<ExpectedLine> =
         serde_json::from_str(&expected_str).expect("Failed to parse expected.json");
 
-    let options = BlameOptions { algorithm };
+    let options = BlameOptions {
+        algorithm,
+        ..Default::default()
+    };
     let result = blame_with_options(&revisions, options).expect("Blame failed");
 
     println!("\nBlame Results:");
@@ -106,14 +87,6 @@ fn run_fixture_test(fixture_dir: &str, algorithm: DiffAlgorithm) {
     println!("\n[ok] {} ({}) passed", fixture_dir, algo_name);
 }
 
-fn run_with_all_algorithms<F>(mut f: F)
-where
-    F: FnMut(DiffAlgorithm),
-{
-    f(DiffAlgorithm::Myers);
-    f(DiffAlgorithm::Patience);
-}
-
 fn normalize_line(line: &str) -> &str {
     line.trim_end_matches(['\r', '\n'])
 }
@@ -165,8 +138,14 @@ fn test_single_revision_preserves_first_metadata() {
     }];
 
     run_with_all_algorithms(|algorithm| {
-        let result = blame_with_options(&revisions, BlameOptions { algorithm })
-            .expect("single revision should succeed");
+        let result = blame_with_options(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("single revision should succeed");
 
         assert_eq!(result.len(), 3);
         assert_eq!(
@@ -204,8 +183,14 @@ fn test_trailing_newline_no_extra_line() {
     ];
 
     run_with_all_algorithms(|algorithm| {
-        let result = blame_with_options(&revisions, BlameOptions { algorithm })
-            .expect("trailing newline inputs should succeed");
+        let result = blame_with_options(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("trailing newline inputs should succeed");
 
         assert_eq!(result.len(), 3);
         assert_eq!(
@@ -263,8 +248,14 @@ fn test_crlf_inputs_are_handled() {
     ];
 
     run_with_all_algorithms(|algorithm| {
-        let result = blame_with_options(&revisions, BlameOptions { algorithm })
-            .expect("CRLF inputs should succeed");
+        let result = blame_with_options(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("CRLF inputs should succeed");
 
         assert_eq!(result.len(), 3);
         assert_eq!(
@@ -322,8 +313,14 @@ fn test_reordered_lines_do_not_panic() {
     ];
 
     run_with_all_algorithms(|algorithm| {
-        let result = blame_with_options(&revisions, BlameOptions { algorithm })
-            .expect("reordered lines should not panic");
+        let result = blame_with_options(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("reordered lines should not panic");
 
         assert_eq!(result.len(), 3);
         assert_eq!(
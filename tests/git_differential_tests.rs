@@ -0,0 +1,162 @@
+mod common;
+
+use blame_rs::{BlameOptions, DiffAlgorithm, blame_with_options};
+use common::{GitRepo, git_available, indexed_revisions, load_revisions};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BLAMED_FILE: &str = "file.txt";
+
+struct GitBlameLine {
+    hash: String,
+    content: String,
+}
+
+fn fixture_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir("tests/fixtures")
+        .expect("Failed to read tests/fixtures")
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn git_algorithm_name(algorithm: DiffAlgorithm) -> &'static str {
+    match algorithm {
+        DiffAlgorithm::Myers => "myers",
+        DiffAlgorithm::Patience => "patience",
+    }
+}
+
+// Parses `git blame --porcelain`: each line starts with a "<hash> <orig> <final> [<count>]"
+// header, followed by optional commit info and the content prefixed by a tab.
+fn parse_porcelain(output: &str) -> Vec<GitBlameLine> {
+    let mut lines = Vec::new();
+    let mut current_hash = None;
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            lines.push(GitBlameLine {
+                hash: current_hash.take().expect("content line without header"),
+                content: content.to_string(),
+            });
+            continue;
+        }
+
+        let mut fields = line.split(' ');
+        if let Some(hash) = fields.next()
+            && hash.len() == 40
+            && hash.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            current_hash = Some(hash.to_string());
+        }
+    }
+
+    lines
+}
+
+/// Compares blame-rs with `git blame` for one fixture, returning one message per divergent line
+fn diff_against_git(fixture_path: &Path, algorithm: DiffAlgorithm) -> Vec<String> {
+    let contents = load_revisions(fixture_path);
+    assert!(
+        !contents.is_empty(),
+        "No revision files found in {:?}",
+        fixture_path
+    );
+
+    let mut repo = GitRepo::new();
+    let mut revision_by_hash = HashMap::new();
+    for (idx, content) in contents.iter().enumerate() {
+        repo.write(BLAMED_FILE, content);
+        let hash = repo.commit("Tester", &format!("rev{}", idx));
+        revision_by_hash.insert(hash, idx);
+    }
+
+    let git_output = repo.git(&[
+        "blame",
+        "--porcelain",
+        &format!("--diff-algorithm={}", git_algorithm_name(algorithm)),
+        "HEAD",
+        "--",
+        BLAMED_FILE,
+    ]);
+    let git_lines = parse_porcelain(&git_output);
+
+    let revisions = indexed_revisions(&contents);
+    let result = blame_with_options(
+        &revisions,
+        BlameOptions {
+            algorithm,
+            ..Default::default()
+        },
+    )
+    .expect("Blame failed");
+
+    let mut divergences = Vec::new();
+    if git_lines.len() != result.len() {
+        divergences.push(format!(
+            "line count: git has {}, blame-rs has {}",
+            git_lines.len(),
+            result.len()
+        ));
+    }
+
+    for (git_line, line) in git_lines.iter().zip(result.lines()) {
+        let git_revision = revision_by_hash[&git_line.hash];
+        let content = line.content.trim_end_matches(['\r', '\n']);
+
+        if git_revision != line.revision_index || git_line.content != content {
+            divergences.push(format!(
+                "line {}: git says rev {} {:?}, blame-rs says rev {} {:?}",
+                line.line_number + 1,
+                git_revision,
+                git_line.content,
+                line.revision_index,
+                content
+            ));
+        }
+    }
+
+    divergences
+}
+
+fn run_differential(algorithm: DiffAlgorithm) {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut report = Vec::new();
+    for fixture_path in fixture_dirs() {
+        let divergences = diff_against_git(&fixture_path, algorithm);
+        println!(
+            "{:?} ({}): {} divergent lines",
+            fixture_path,
+            git_algorithm_name(algorithm),
+            divergences.len()
+        );
+
+        for divergence in divergences {
+            report.push(format!("{}: {}", fixture_path.display(), divergence));
+        }
+    }
+
+    assert!(
+        report.is_empty(),
+        "blame-rs diverges from git blame ({}):\n{}",
+        git_algorithm_name(algorithm),
+        report.join("\n")
+    );
+}
+
+#[test]
+fn test_matches_git_blame_myers() {
+    run_differential(DiffAlgorithm::Myers);
+}
+
+#[test]
+fn test_matches_git_blame_patience() {
+    run_differential(DiffAlgorithm::Patience);
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use blame_rs::{BlameError, GitHistory, blame};
+use common::{GitRepo, git_available};
+
+#[test]
+fn test_git_history_attributes_commits() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("notes.txt", "a\nb\n");
+    let first = repo.commit("Alice", "Initial notes");
+    repo.write("other.txt", "unrelated\n");
+    repo.commit("Bob", "Unrelated change");
+    repo.write("notes.txt", "a\nb\nc\n");
+    let third = repo.commit("Charlie", "Add c");
+
+    let history = GitHistory::load(repo.path(), "notes.txt", "HEAD").expect("load history");
+    assert_eq!(history.len(), 2);
+
+    let commits = history.commits();
+    assert_eq!(commits[0].hash, first);
+    assert_eq!(commits[0].author, "Alice");
+    assert_eq!(commits[0].author_email, "alice@example.com");
+    assert_eq!(commits[0].summary, "Initial notes");
+    assert_eq!(commits[1].hash, third);
+    assert!(commits[0].author_time < commits[1].author_time);
+
+    let revisions = history.revisions();
+    let result = blame(&revisions).expect("blame should succeed");
+    let authors: Vec<&str> = result
+        .iter()
+        .map(|line| line.revision_metadata.author.as_str())
+        .collect();
+    assert_eq!(authors, vec!["Alice", "Alice", "Charlie"]);
+}
+
+#[test]
+fn test_git_history_follows_renames() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("old.txt", "one\ntwo\nthree\n");
+    repo.commit("Alice", "Create old.txt");
+    repo.rename("old.txt", "docs/new.txt");
+    repo.commit("Bob", "Move to docs/new.txt");
+    repo.write("docs/new.txt", "one\ntwo\nthree\nfour\n");
+    repo.commit("Charlie", "Add four");
+
+    let history = GitHistory::load(repo.path(), "docs/new.txt", "HEAD").expect("load history");
+    let paths: Vec<&str> = history
+        .commits()
+        .iter()
+        .map(|commit| commit.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["old.txt", "docs/new.txt", "docs/new.txt"]);
+
+    let revisions = history.revisions();
+    let result = blame(&revisions).expect("blame should succeed");
+    let authors: Vec<&str> = result
+        .iter()
+        .map(|line| line.revision_metadata.author.as_str())
+        .collect();
+    assert_eq!(authors, vec!["Alice", "Alice", "Alice", "Charlie"]);
+}
+
+#[test]
+fn test_git_history_starts_at_given_revision() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("file.txt", "a\n");
+    let first = repo.commit("Alice", "First");
+    repo.write("file.txt", "a\nb\n");
+    repo.commit("Bob", "Second");
+
+    let history = GitHistory::load(repo.path(), "file.txt", &first).expect("load history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.revisions()[0].content, "a\n");
+}
+
+#[test]
+fn test_git_history_missing_path_is_an_error() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("file.txt", "a\n");
+    repo.commit("Alice", "First");
+
+    let err =
+        GitHistory::load(repo.path(), "missing.txt", "HEAD").expect_err("missing file should fail");
+    assert!(matches!(err, BlameError::Git(_)));
+}
+
+#[test]
+fn test_git_history_rejects_option_like_revision() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("file.txt", "a\n");
+    repo.commit("Alice", "First");
+
+    let output = repo.path().join("written-by-git.txt");
+    let rev = format!("--output={}", output.display());
+    let err = GitHistory::load(repo.path(), "file.txt", &rev).expect_err("option should fail");
+    assert!(matches!(err, BlameError::Git(_)));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_git_history_reports_undecodable_revision() {
+    if !git_available() {
+        eprintln!("git not found, skipping");
+        return;
+    }
+
+    let mut repo = GitRepo::new();
+    repo.write("menu.txt", "menu\n");
+    repo.commit("Alice", "UTF-8 menu");
+    // "café" encoded as Latin-1
+    std::fs::write(repo.path().join("menu.txt"), b"menu\ncaf\xe9\n").expect("write Latin-1");
+    repo.commit("Bob", "Latin-1 menu");
+
+    let err = GitHistory::load(repo.path(), "menu.txt", "HEAD").expect_err("should fail to decode");
+    assert!(matches!(
+        err,
+        BlameError::InvalidUtf8 {
+            revision_index: 1,
+            valid_up_to: 8,
+        }
+    ));
+    assert_eq!(err.revision_index(), Some(1));
+}
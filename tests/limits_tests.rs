@@ -0,0 +1,178 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameLimits, BlameOptions, ByteRevision, blame_bytes, blame_iter,
+    blame_with_options, blame_words,
+};
+use common::revisions;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn with_limits(limits: BlameLimits) -> BlameOptions {
+    BlameOptions {
+        limits,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_limits_are_disabled_by_default() {
+    let long_line = "x".repeat(100_000);
+    let binary = "a\0b\n";
+    let revisions = revisions(&[&long_line, binary]);
+
+    assert!(blame_with_options(&revisions, BlameOptions::default()).is_ok());
+}
+
+#[test]
+fn test_max_revisions() {
+    let revisions = revisions(&["a\n", "a\nb\n", "a\nb\nc\n"]);
+    let options = with_limits(BlameLimits {
+        max_revisions: Some(2),
+        ..Default::default()
+    });
+
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::TooManyRevisions {
+            revision_index: 2,
+            limit: 2
+        })
+    ));
+    assert!(blame_with_options(&revisions[..2], options).is_ok());
+
+    // Streaming input is checked as revisions arrive
+    assert!(matches!(
+        blame_iter(revisions, options),
+        Err(BlameError::TooManyRevisions {
+            revision_index: 2,
+            limit: 2
+        })
+    ));
+}
+
+#[test]
+fn test_max_lines() {
+    let revisions = revisions(&["a\nb\n", "a\nb\nc\nd\n"]);
+    let options = with_limits(BlameLimits {
+        max_lines: Some(3),
+        ..Default::default()
+    });
+
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::TooManyLines {
+            revision_index: 1,
+            lines: 4,
+            limit: 3,
+        })
+    ));
+}
+
+#[test]
+fn test_max_lines_counts_tokens_in_word_blame() {
+    let revisions = revisions(&["one two three\n"]);
+    let options = with_limits(BlameLimits {
+        max_lines: Some(3),
+        ..Default::default()
+    });
+
+    assert!(blame_with_options(&revisions, options).is_ok());
+    assert!(matches!(
+        blame_words(&revisions, options),
+        Err(BlameError::TooManyLines { lines: 6, .. })
+    ));
+}
+
+#[test]
+fn test_max_line_length() {
+    let minified = format!("header\n{}\n", "var a=1;".repeat(1_000));
+    let revisions = revisions(&["header\n", &minified]);
+    let options = with_limits(BlameLimits {
+        max_line_length: Some(1_000),
+        ..Default::default()
+    });
+
+    match blame_with_options(&revisions, options) {
+        Err(BlameError::LineTooLong {
+            revision_index,
+            line_number,
+            length,
+            limit,
+        }) => {
+            assert_eq!(revision_index, 1);
+            assert_eq!(line_number, 1);
+            assert_eq!(length, 8_001);
+            assert_eq!(limit, 1_000);
+        }
+        other => panic!("expected LineTooLong, got {:?}", other.map(|r| r.len())),
+    }
+}
+
+#[test]
+fn test_binary_detection() {
+    let revisions = revisions(&["text\n", "text\n\0\x01\x02\n"]);
+    let options = with_limits(BlameLimits {
+        reject_binary: true,
+        ..Default::default()
+    });
+
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::BinaryContent { revision_index: 1 })
+    ));
+}
+
+#[test]
+fn test_binary_detection_only_sniffs_the_start() {
+    let late_nul = format!("{}\0\n", "a\n".repeat(5_000));
+    let revisions = revisions(&[&late_nul]);
+    let options = with_limits(BlameLimits {
+        reject_binary: true,
+        ..Default::default()
+    });
+
+    assert!(blame_with_options(&revisions, options).is_ok());
+}
+
+#[test]
+fn test_binary_detection_on_bytes() {
+    let revisions = vec![ByteRevision {
+        content: &b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"[..],
+        metadata: Rc::new(0),
+    }];
+    let options = with_limits(BlameLimits {
+        reject_binary: true,
+        ..Default::default()
+    });
+
+    assert!(matches!(
+        blame_bytes(&revisions, options),
+        Err(BlameError::BinaryContent { revision_index: 0 })
+    ));
+}
+
+#[test]
+fn test_diff_deadline() {
+    let old: String = (0..5_000).map(|i| format!("old {}\n", i)).collect();
+    let new: String = (0..5_000).map(|i| format!("new {}\n", i)).collect();
+    let revisions = revisions(&[&old, &new]);
+
+    let options = with_limits(BlameLimits {
+        diff_deadline: Some(Duration::ZERO),
+        ..Default::default()
+    });
+    assert!(matches!(
+        blame_with_options(&revisions, options),
+        Err(BlameError::DeadlineExceeded {
+            revision_index: 1,
+            ..
+        })
+    ));
+
+    let options = with_limits(BlameLimits {
+        diff_deadline: Some(Duration::from_secs(60)),
+        ..Default::default()
+    });
+    assert!(blame_with_options(&revisions, options).is_ok());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameOptions, BlameRevision, DiffAlgorithm, LineHistory, blame_with_options,
+    line_histories, line_history,
+};
+use common::{TestMetadata, revisions, run_with_all_algorithms};
+
+fn options(algorithm: DiffAlgorithm) -> BlameOptions {
+    BlameOptions {
+        algorithm,
+        ..Default::default()
+    }
+}
+
+fn steps<'a>(history: &LineHistory<'a, TestMetadata>) -> Vec<(usize, usize, &'a str)> {
+    history
+        .iter()
+        .map(|entry| (entry.revision_index, entry.line_number, entry.content))
+        .collect()
+}
+
+#[test]
+fn test_history_follows_every_edit() {
+    let revisions = revisions(&[
+        "fn main() {\n    let x = 1;\n}\n",
+        "// entry point\nfn main() {\n    let x = 1;\n}\n",
+        "// entry point\nfn main() {\n    let x = 2;\n}\n",
+        "// entry point\nfn main() {\n    let x = 2;\n    let y = 3;\n}\n",
+        "// entry point\nfn main() {\n    let x: u8 = 2;\n    let y = 3;\n}\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let history = line_history(&revisions, options(algorithm), 2).unwrap();
+
+        assert_eq!(history.line_number(), 2);
+        assert_eq!(
+            steps(&history),
+            vec![
+                (4, 2, "    let x: u8 = 2;\n"),
+                (2, 2, "    let x = 2;\n"),
+                (0, 1, "    let x = 1;\n"),
+            ]
+        );
+        assert_eq!(history.introduced().unwrap().revision_index, 0);
+        assert_eq!(history.entries()[1].revision_metadata.revision, 2);
+    });
+}
+
+#[test]
+fn test_untouched_line_has_single_entry() {
+    let revisions = revisions(&["a\nb\n", "a\nb\nc\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let history = line_history(&revisions, options(algorithm), 0).unwrap();
+        assert_eq!(steps(&history), vec![(0, 0, "a\n")]);
+
+        let history = line_history(&revisions, options(algorithm), 2).unwrap();
+        assert_eq!(steps(&history), vec![(1, 2, "c\n")]);
+    });
+}
+
+#[test]
+fn test_newest_entry_matches_blame() {
+    let revisions = revisions(&[
+        "alpha\nbeta\ngamma\n",
+        "alpha\nbeta 2\ngamma\n",
+        "delta\nalpha\nbeta 2.1\ngamma\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let blame = blame_with_options(&revisions, options(algorithm)).unwrap();
+        let histories = line_histories(&revisions, options(algorithm)).unwrap();
+        assert_eq!(histories.len(), blame.len());
+
+        for (history, line) in histories.iter().zip(blame.iter()) {
+            assert_eq!(history.line_number(), line.line_number);
+            let newest = &history.entries()[0];
+            assert_eq!(newest.revision_index, line.revision_index);
+            assert_eq!(newest.content, line.content);
+        }
+
+        assert_eq!(
+            steps(&histories[2]),
+            vec![(2, 2, "beta 2.1\n"), (1, 1, "beta 2\n"), (0, 1, "beta\n")]
+        );
+    });
+}
+
+#[test]
+fn test_explicit_threshold_is_respected() {
+    let revisions = revisions(&["let value = 1;\n", "let value = 2;\n"]);
+
+    let strict = BlameOptions {
+        similarity_threshold: Some(100),
+        ..Default::default()
+    };
+    let history = line_history(&revisions, strict, 0).unwrap();
+    assert_eq!(steps(&history), vec![(1, 0, "let value = 2;\n")]);
+
+    let history = line_history(&revisions, BlameOptions::default(), 0).unwrap();
+    assert_eq!(history.len(), 2);
+}
+
+#[test]
+fn test_out_of_range_line_is_an_error() {
+    let revisions = revisions(&["only\n"]);
+
+    assert!(matches!(
+        line_history(&revisions, BlameOptions::default(), 1),
+        Err(BlameError::LineOutOfRange {
+            line_number: 1,
+            lines: 1,
+        })
+    ));
+}
+
+#[test]
+fn test_empty_revisions() {
+    let revisions: Vec<BlameRevision<TestMetadata>> = Vec::new();
+
+    assert!(matches!(
+        line_histories(&revisions, BlameOptions::default()),
+        Err(BlameError::EmptyRevisions)
+    ));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use blame_rs::{
+    BlameLimits, BlameOptions, DeadlineFallback, DiffAlgorithm, blame_snapshots, blame_with_options,
+};
+use common::{revisions, run_with_all_algorithms};
+use std::time::Duration;
+
+fn reporting(algorithm: DiffAlgorithm) -> BlameOptions {
+    BlameOptions {
+        algorithm,
+        report_empty_revisions: true,
+        ..Default::default()
+    }
+}
+
+// 0: base, 1: duplicate, 2: adds c, 3: deletes a, 4: duplicate, 5: adds d then 6 rewrites it
+const HISTORY: [&str; 7] = [
+    "a\nb\n",
+    "a\nb\n",
+    "a\nb\nc\n",
+    "b\nc\n",
+    "b\nc\n",
+    "b\nc\nd\n",
+    "b\nc\nD\n",
+];
+
+#[test]
+fn test_identical_revisions_keep_attribution() {
+    let revisions = revisions(&HISTORY);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, reporting(algorithm)).unwrap();
+        let attribution: Vec<(&str, usize)> = result
+            .iter()
+            .map(|line| (line.content, line.revision_metadata.revision))
+            .collect();
+        assert_eq!(attribution, vec![("b\n", 0), ("c\n", 2), ("D\n", 6)]);
+    });
+}
+
+#[test]
+fn test_empty_revisions() {
+    let revisions = revisions(&HISTORY);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, reporting(algorithm)).unwrap();
+        assert_eq!(result.empty_revisions(), &[1, 3, 4]);
+    });
+
+    // Off unless asked for
+    let result = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+    assert!(result.empty_revisions().is_empty());
+}
+
+#[test]
+fn test_empty_first_revision() {
+    let revisions = revisions(&["", "a\n"]);
+    let result = blame_with_options(&revisions, reporting(DiffAlgorithm::Myers)).unwrap();
+    assert_eq!(result.empty_revisions(), &[0]);
+}
+
+#[test]
+fn test_revisions_without_lines() {
+    let revisions = revisions(&HISTORY);
+    let result = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+
+    assert_eq!(result.revision_count(), 7);
+    // Revision 5's line was rewritten by revision 6, so it has none left either
+    assert_eq!(result.revisions_without_lines(), vec![1, 3, 4, 5]);
+}
+
+#[test]
+fn test_snapshots_report_up_to_their_revision() {
+    let revisions = revisions(&HISTORY);
+    let snapshots = blame_snapshots(&revisions, reporting(DiffAlgorithm::Myers)).unwrap();
+
+    assert_eq!(snapshots[2].revision_count(), 3);
+    assert_eq!(snapshots[2].empty_revisions(), &[1]);
+    assert_eq!(snapshots[2].revisions_without_lines(), vec![1]);
+    assert_eq!(snapshots[6].empty_revisions(), &[1, 3, 4]);
+}
+
+#[test]
+fn test_identical_revisions_skip_the_diff() {
+    let content: String = (0..3_000).map(|i| format!("line {}\n", i)).collect();
+    let revisions = revisions(&[&content, &content, &content]);
+    // A zero deadline would approximate any diff that actually ran
+    let options = BlameOptions {
+        limits: BlameLimits {
+            diff_deadline: Some(Duration::ZERO),
+            deadline_fallback: DeadlineFallback::Replace,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let result = blame_with_options(&revisions, options).unwrap();
+    assert!(result.approximated_revisions().is_empty());
+    assert!(result.iter().all(|line| line.revision_index == 0));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use blame_rs::{
+    BlameOptions, BlameResult, BlameRevision, OwnedRevision, blame, blame_snapshots,
+    blame_with_options, blame_words,
+};
+use common::{TestMetadata, run_with_all_algorithms};
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+const HISTORY: [&str; 3] = ["a\nb\n", "a\nb\nc\n", "z\na\nc\n"];
+
+fn owned<C>(make: impl Fn(&'static str) -> C) -> Vec<OwnedRevision<TestMetadata, C>> {
+    HISTORY
+        .iter()
+        .enumerate()
+        .map(|(idx, &content)| OwnedRevision {
+            content: make(content),
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect()
+}
+
+fn attribution<'a>(result: &BlameResult<'a, TestMetadata>) -> Vec<(&'a str, usize)> {
+    result
+        .iter()
+        .map(|line| (line.content, line.revision_metadata.revision))
+        .collect()
+}
+
+fn expected() -> Vec<(&'static str, usize)> {
+    vec![("z\n", 2), ("a\n", 0), ("c\n", 1)]
+}
+
+#[test]
+fn test_string_content() {
+    let revisions = owned(String::from);
+
+    run_with_all_algorithms(|algorithm| {
+        let options = BlameOptions {
+            algorithm,
+            ..Default::default()
+        };
+        let result = blame_with_options(&revisions, options).unwrap();
+        assert_eq!(attribution(&result), expected());
+    });
+}
+
+#[test]
+fn test_arc_str_content() {
+    let revisions = owned(Arc::<str>::from);
+    let result = blame(&revisions).unwrap();
+
+    assert_eq!(attribution(&result), expected());
+}
+
+#[test]
+fn test_cow_content() {
+    let revisions: Vec<OwnedRevision<TestMetadata, Cow<str>>> = HISTORY
+        .iter()
+        .enumerate()
+        .map(|(idx, &content)| OwnedRevision {
+            // Mix borrowed and owned content in one history
+            content: if idx % 2 == 0 {
+                Cow::Borrowed(content)
+            } else {
+                Cow::Owned(content.to_string())
+            },
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect();
+
+    let result = blame(&revisions).unwrap();
+    assert_eq!(attribution(&result), expected());
+}
+
+#[test]
+fn test_owned_matches_borrowed() {
+    let owned_revisions = owned(String::from);
+    let borrowed_revisions: Vec<BlameRevision<TestMetadata>> = owned_revisions
+        .iter()
+        .map(|revision| BlameRevision {
+            content: &revision.content,
+            metadata: Rc::clone(&revision.metadata),
+        })
+        .collect();
+
+    let owned_snapshots = blame_snapshots(&owned_revisions, BlameOptions::default()).unwrap();
+    let borrowed_snapshots = blame_snapshots(&borrowed_revisions, BlameOptions::default()).unwrap();
+
+    for (a, b) in owned_snapshots.iter().zip(&borrowed_snapshots) {
+        let a: Vec<_> = a
+            .iter()
+            .map(|line| (line.content, line.revision_index))
+            .collect();
+        let b: Vec<_> = b
+            .iter()
+            .map(|line| (line.content, line.revision_index))
+            .collect();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_owned_content_with_word_blame() {
+    let revisions = owned(String::from);
+    let result = blame_words(&revisions, BlameOptions::default()).unwrap();
+
+    assert_eq!(result.len(), 3);
+    assert_eq!(result.get_line(0).unwrap().content, "z\n");
+    assert_eq!(result.get_line(0).unwrap().spans[0].revision_index, 2);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use blame_rs::{BlameOptions, DiffAlgorithm, blame_with_options};
+use common::{revisions, run_with_all_algorithms};
+
+fn options(algorithm: DiffAlgorithm, similarity_threshold: Option<u8>) -> BlameOptions {
+    BlameOptions {
+        algorithm,
+        similarity_threshold,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_edited_line_records_previous() {
+    let revisions = revisions(&[
+        "fn main() {\n    println!(\"Hello\");\n}\n",
+        "fn main() {\n    println!(\"Hello, world\");\n}\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(50))).unwrap();
+
+        let edited = result.get_line(1).expect("line 1");
+        assert_eq!(edited.revision_index, 1);
+        let previous = edited
+            .previous
+            .as_ref()
+            .expect("edited line has a previous");
+        assert_eq!(previous.revision_index, 0);
+        assert_eq!(previous.line_number, 1);
+        assert_eq!(previous.content, "    println!(\"Hello\");\n");
+        assert_eq!(previous.revision_metadata.revision, 0);
+
+        assert!(result.get_line(0).unwrap().previous.is_none());
+        assert!(result.get_line(2).unwrap().previous.is_none());
+    });
+}
+
+#[test]
+fn test_disabled_by_default() {
+    let revisions = revisions(&["let x = 1;\n", "let x = 2;\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, None)).unwrap();
+        assert!(result.iter().all(|line| line.previous.is_none()));
+    });
+
+    assert_eq!(BlameOptions::default().similarity_threshold, None);
+}
+
+#[test]
+fn test_dissimilar_replacement_has_no_previous() {
+    let revisions = revisions(&["alpha beta gamma\n", "0123456789\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(50))).unwrap();
+        let line = result.get_line(0).unwrap();
+        assert_eq!(line.revision_index, 1);
+        assert!(line.previous.is_none());
+    });
+}
+
+#[test]
+fn test_pure_insertion_has_no_previous() {
+    let revisions = revisions(&["a\nc\n", "a\nb\nc\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(0))).unwrap();
+        assert!(result.iter().all(|line| line.previous.is_none()));
+    });
+}
+
+#[test]
+fn test_hunk_lines_pair_in_order_by_similarity() {
+    let revisions = revisions(&[
+        "start\nlet width = 10;\nlet height = 20;\nend\n",
+        "start\n// dimensions\nlet width = 12;\nlet height = 24;\nend\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(60))).unwrap();
+        let previous: Vec<Option<usize>> = result
+            .iter()
+            .map(|line| line.previous.as_ref().map(|previous| previous.line_number))
+            .collect();
+
+        assert_eq!(previous, vec![None, None, Some(1), Some(2), None]);
+    });
+}
+
+#[test]
+fn test_previous_points_at_the_revision_before_the_edit() {
+    let revisions = revisions(&[
+        "value = 1\n",
+        "value = 1\nunrelated\n",
+        "value = 10\nunrelated\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(50))).unwrap();
+        let line = result.get_line(0).unwrap();
+        assert_eq!(line.revision_index, 2);
+
+        // The line was last touched in revision 2; git's `previous` names its parent
+        let previous = line.previous.as_ref().unwrap();
+        assert_eq!(previous.revision_index, 1);
+        assert_eq!(previous.revision_metadata.revision, 1);
+        assert_eq!(previous.content, "value = 1\n");
+    });
+}
+
+#[test]
+fn test_line_terminator_change_pairs_fully() {
+    let revisions = revisions(&["one\r\ntwo\r\n", "one\ntwo\n"]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(100))).unwrap();
+        for line in result.iter() {
+            let previous = line.previous.as_ref().expect("paired");
+            assert_eq!(previous.line_number, line.line_number);
+        }
+    });
+}
+
+#[test]
+fn test_uniformly_edited_hunk_pairs_line_for_line() {
+    // Every call gains the same extra argument, which makes each edited line look even more
+    // like the next original line than like its own
+    let call = |args: usize, extra: &str| {
+        let args: Vec<String> = (0..args).map(|arg| format!("a{}", arg)).collect();
+        format!("call({}{});\n", args.join(", "), extra)
+    };
+    let old: String = (1..=40).map(|args| call(args, "")).collect();
+    let new: String = (1..=40).map(|args| call(args, ", extra")).collect();
+    let revisions = revisions(&[&old, &new]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_with_options(&revisions, options(algorithm, Some(60))).unwrap();
+        for line in result.iter() {
+            let previous = line.previous.as_ref().expect("every edited line is paired");
+            assert_eq!(previous.line_number, line.line_number);
+        }
+    });
+}
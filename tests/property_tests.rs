@@ -0,0 +1,269 @@
+mod common;
+
+use blame_rs::{
+    BlameOptions, BlameResult, BlameRevision, BlameStrategy, DiffAlgorithm, blame_with_options,
+};
+use common::indexed_revisions;
+use proptest::prelude::*;
+
+/// A single edit applied to the previous revision's lines
+#[derive(Debug, Clone)]
+enum Edit {
+    /// Insert `count` new lines before position `at` (taken modulo the line count + 1)
+    Insert { at: usize, count: usize },
+    /// Delete up to `count` lines starting at position `at` (taken modulo the line count)
+    Delete { at: usize, count: usize },
+}
+
+/// A generated history: the initial line count followed by one edit script per revision
+#[derive(Debug, Clone)]
+struct EditHistory {
+    initial_lines: usize,
+    scripts: Vec<Vec<Edit>>,
+}
+
+/// A line in a generated revision, tagged with the revision that physically inserted it
+#[derive(Debug, Clone)]
+struct GeneratedLine {
+    text: String,
+    inserted_at: usize,
+}
+
+fn edit_strategy() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        (any::<usize>(), 1..4usize).prop_map(|(at, count)| Edit::Insert { at, count }),
+        (any::<usize>(), 1..4usize).prop_map(|(at, count)| Edit::Delete { at, count }),
+    ]
+}
+
+fn history_strategy() -> impl Strategy<Value = EditHistory> {
+    (
+        0..20usize,
+        prop::collection::vec(prop::collection::vec(edit_strategy(), 0..5), 0..8),
+    )
+        .prop_map(|(initial_lines, scripts)| EditHistory {
+            initial_lines,
+            scripts,
+        })
+}
+
+/// Replays an edit history, naming each new line with `name(revision, serial)`
+fn build_revisions<F>(history: &EditHistory, mut name: F) -> Vec<Vec<GeneratedLine>>
+where
+    F: FnMut(usize, usize) -> String,
+{
+    let mut serial = 0;
+    let mut new_line = |revision: usize| {
+        serial += 1;
+        GeneratedLine {
+            text: format!("{}\n", name(revision, serial)),
+            inserted_at: revision,
+        }
+    };
+
+    let mut current: Vec<GeneratedLine> = (0..history.initial_lines).map(|_| new_line(0)).collect();
+    let mut revisions = vec![current.clone()];
+
+    for (idx, script) in history.scripts.iter().enumerate() {
+        let revision = idx + 1;
+        for edit in script {
+            match *edit {
+                Edit::Insert { at, count } => {
+                    let at = at % (current.len() + 1);
+                    for offset in 0..count {
+                        current.insert(at + offset, new_line(revision));
+                    }
+                }
+                Edit::Delete { at, count } => {
+                    if current.is_empty() {
+                        continue;
+                    }
+                    let at = at % current.len();
+                    let end = (at + count).min(current.len());
+                    current.drain(at..end);
+                }
+            }
+        }
+        revisions.push(current.clone());
+    }
+
+    revisions
+}
+
+fn join_lines(lines: &[GeneratedLine]) -> String {
+    lines.iter().map(|line| line.text.as_str()).collect()
+}
+
+fn run_blame<'a>(
+    revisions: &'a [BlameRevision<'a, usize>],
+    algorithm: DiffAlgorithm,
+) -> BlameResult<'a, usize> {
+    blame_with_options(
+        revisions,
+        BlameOptions {
+            algorithm,
+            ..Default::default()
+        },
+    )
+    .expect("blame should succeed")
+}
+
+const ALGORITHMS: [DiffAlgorithm; 2] = [DiffAlgorithm::Myers, DiffAlgorithm::Patience];
+
+proptest! {
+    #[test]
+    fn prop_result_content_equals_final_revision(
+        history in history_strategy(),
+        alphabet in 1..6usize,
+    ) {
+        // A small alphabet produces many duplicate lines and ambiguous diffs
+        let generated = build_revisions(&history, |_, serial| format!("x{}", serial % alphabet));
+        let contents: Vec<String> = generated.iter().map(|lines| join_lines(lines)).collect();
+        let revisions = indexed_revisions(&contents);
+
+        for algorithm in ALGORITHMS {
+            let result = run_blame(&revisions, algorithm);
+            let reconstructed: String = result.iter().map(|line| line.content).collect();
+            prop_assert_eq!(&reconstructed, contents.last().unwrap());
+
+            for (idx, line) in result.iter().enumerate() {
+                prop_assert_eq!(line.line_number, idx);
+                prop_assert_eq!(*line.revision_metadata, line.revision_index);
+            }
+        }
+    }
+
+    #[test]
+    fn prop_unique_lines_are_attributed_to_their_inserting_revision(
+        history in history_strategy(),
+    ) {
+        let generated = build_revisions(&history, |revision, serial| format!("r{}-{}", revision, serial));
+        let contents: Vec<String> = generated.iter().map(|lines| join_lines(lines)).collect();
+        let revisions = indexed_revisions(&contents);
+        let final_lines = generated.last().unwrap();
+
+        for algorithm in ALGORITHMS {
+            let result = run_blame(&revisions, algorithm);
+            prop_assert_eq!(result.len(), final_lines.len());
+
+            for (line, expected) in result.iter().zip(final_lines) {
+                prop_assert_eq!(
+                    line.revision_index,
+                    expected.inserted_at,
+                    "{:?} with {:?}",
+                    line.content,
+                    algorithm
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn prop_revisions_without_insertions_are_never_attributed(
+        history in history_strategy(),
+        alphabet in 1..6usize,
+    ) {
+        let generated = build_revisions(&history, |_, serial| format!("x{}", serial % alphabet));
+        let contents: Vec<String> = generated.iter().map(|lines| join_lines(lines)).collect();
+        let revisions = indexed_revisions(&contents);
+
+        // Revision k physically inserted nothing, so no final line can come from it
+        let inserting: Vec<bool> = (0..generated.len())
+            .map(|revision| {
+                revision == 0
+                    || generated[revision]
+                        .iter()
+                        .any(|line| line.inserted_at == revision)
+            })
+            .collect();
+
+        for algorithm in ALGORITHMS {
+            let result = run_blame(&revisions, algorithm);
+            for line in result.iter() {
+                prop_assert!(
+                    inserting[line.revision_index],
+                    "line {} attributed to revision {} which inserted nothing ({:?})",
+                    line.line_number,
+                    line.revision_index,
+                    algorithm
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn prop_single_revision_attributes_everything_to_first(
+        lines in prop::collection::vec("[a-c ]{0,6}", 0..30),
+        trailing_newline in any::<bool>(),
+    ) {
+        let mut content = lines.join("\n");
+        if trailing_newline && !content.is_empty() {
+            content.push('\n');
+        }
+        let contents = vec![content];
+        let revisions = indexed_revisions(&contents);
+
+        for algorithm in ALGORITHMS {
+            let result = run_blame(&revisions, algorithm);
+            let reconstructed: String = result.iter().map(|line| line.content).collect();
+            prop_assert_eq!(&reconstructed, &contents[0]);
+            prop_assert!(result.iter().all(|line| line.revision_index == 0));
+        }
+    }
+
+    #[test]
+    fn prop_algorithms_agree_on_untouched_lines(
+        history in history_strategy(),
+    ) {
+        let generated = build_revisions(&history, |revision, serial| format!("r{}-{}", revision, serial));
+        let contents: Vec<String> = generated.iter().map(|lines| join_lines(lines)).collect();
+        let revisions = indexed_revisions(&contents);
+
+        let myers = run_blame(&revisions, DiffAlgorithm::Myers);
+        let patience = run_blame(&revisions, DiffAlgorithm::Patience);
+
+        // With unique lines, every surviving line is untouched after its insertion
+        for (a, b) in myers.iter().zip(patience.iter()) {
+            prop_assert_eq!(a.content, b.content);
+            prop_assert_eq!(a.revision_index, b.revision_index);
+        }
+    }
+
+    #[test]
+    fn prop_backward_matches_forward(
+        history in history_strategy(),
+        alphabet in 1..6usize,
+        similarity_threshold in prop::option::of(0..=100u8),
+    ) {
+        let generated = build_revisions(&history, |_, serial| format!("x{}", serial % alphabet));
+        let contents: Vec<String> = generated.iter().map(|lines| join_lines(lines)).collect();
+        let revisions = indexed_revisions(&contents);
+
+        for algorithm in ALGORITHMS {
+            let options = BlameOptions {
+                algorithm,
+                similarity_threshold,
+                ..Default::default()
+            };
+            let forward = blame_with_options(&revisions, options).unwrap();
+            let backward = blame_with_options(
+                &revisions,
+                BlameOptions {
+                    strategy: BlameStrategy::Backward,
+                    ..options
+                },
+            )
+            .unwrap();
+
+            prop_assert_eq!(backward.len(), forward.len());
+            for (a, b) in backward.iter().zip(forward.iter()) {
+                prop_assert_eq!(a.content, b.content);
+                prop_assert_eq!(a.revision_index, b.revision_index);
+                prop_assert_eq!(
+                    a.previous.as_ref().map(|previous| (previous.revision_index, previous.line_number)),
+                    b.previous.as_ref().map(|previous| (previous.revision_index, previous.line_number))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use blame_rs::{BlameRevision, HeatmapFormat, blame, render_heatmap, render_heatmap_by};
+use std::rc::Rc;
+
+#[derive(Debug)]
+struct TestMetadata {
+    timestamp: i64,
+}
+
+const OLDEST_ANSI: &str = "\x1b[48;2;255;241;229m";
+const NEWEST_ANSI: &str = "\x1b[48;2;71;23;0m";
+
+fn three_revisions() -> Vec<BlameRevision<'static, TestMetadata>> {
+    vec![
+        BlameRevision {
+            content: "a\nb\n",
+            metadata: Rc::new(TestMetadata { timestamp: 100 }),
+        },
+        BlameRevision {
+            content: "a\nb\nc\n",
+            metadata: Rc::new(TestMetadata { timestamp: 200 }),
+        },
+        BlameRevision {
+            content: "a\nb\nc\n<d & e>\n",
+            metadata: Rc::new(TestMetadata { timestamp: 10_000 }),
+        },
+    ]
+}
+
+#[test]
+fn test_ansi_heatmap_colors_by_revision_index() {
+    let revisions = three_revisions();
+    let result = blame(&revisions).expect("blame should succeed");
+
+    let rendered = render_heatmap(&result, HeatmapFormat::Ansi);
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with(OLDEST_ANSI));
+    assert!(lines[1].starts_with(OLDEST_ANSI));
+    assert!(lines[2].starts_with("\x1b[48;2;225;111;36m"));
+    assert!(lines[3].starts_with(NEWEST_ANSI));
+    assert!(lines[3].ends_with("r2 4 | <d & e>"));
+}
+
+#[test]
+fn test_heatmap_by_timestamp_scales_between_extremes() {
+    let revisions = three_revisions();
+    let result = blame(&revisions).expect("blame should succeed");
+
+    let rendered = render_heatmap_by(&result, HeatmapFormat::Ansi, |line| {
+        line.revision_metadata.timestamp
+    });
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    // Revision 1 is barely newer than revision 0 on the timestamp scale
+    assert!(lines[2].starts_with(OLDEST_ANSI));
+    assert!(lines[3].starts_with(NEWEST_ANSI));
+}
+
+#[test]
+fn test_html_heatmap_escapes_content() {
+    let revisions = three_revisions();
+    let result = blame(&revisions).expect("blame should succeed");
+
+    let rendered = render_heatmap(&result, HeatmapFormat::Html);
+
+    assert!(rendered.starts_with("<table class=\"blame-heatmap\">\n"));
+    assert!(rendered.ends_with("</table>\n"));
+    assert!(rendered.contains("background-color:#fff1e5"));
+    assert!(rendered.contains("background-color:#471700"));
+    assert!(rendered.contains("<pre>&lt;d &amp; e&gt;</pre>"));
+}
+
+#[test]
+fn test_single_revision_uses_newest_color() {
+    let revisions = vec![BlameRevision {
+        content: "only\nlines\n",
+        metadata: Rc::new(TestMetadata { timestamp: 0 }),
+    }];
+    let result = blame(&revisions).expect("blame should succeed");
+
+    let rendered = render_heatmap(&result, HeatmapFormat::Ansi);
+    for line in rendered.lines() {
+        assert!(line.starts_with(NEWEST_ANSI));
+    }
+}
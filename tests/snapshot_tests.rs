@@ -0,0 +1,117 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameOptions, BlameResult, BlameRevision, DiffAlgorithm, blame_snapshots,
+    blame_snapshots_at, blame_with_options,
+};
+use common::{TestMetadata, revisions, run_with_all_algorithms};
+use std::rc::Rc;
+
+fn options(algorithm: DiffAlgorithm) -> BlameOptions {
+    BlameOptions {
+        algorithm,
+        ..Default::default()
+    }
+}
+
+fn attribution<'a>(result: &BlameResult<'a, TestMetadata>) -> Vec<(&'a str, usize)> {
+    result
+        .iter()
+        .map(|line| (line.content, line.revision_metadata.revision))
+        .collect()
+}
+
+const HISTORY: [&str; 5] = [
+    "a\nb\nc\n",
+    "a\nB\nc\nd\n",
+    "x\na\nB\nc\nd\n",
+    "x\na\nc\nd\n",
+    "x\na\nc\nd\ne\nf\n",
+];
+
+#[test]
+fn test_snapshots_match_blame_of_each_prefix() {
+    let revisions = revisions(&HISTORY);
+
+    run_with_all_algorithms(|algorithm| {
+        let snapshots = blame_snapshots(&revisions, options(algorithm)).unwrap();
+        assert_eq!(snapshots.len(), revisions.len());
+
+        for (k, snapshot) in snapshots.iter().enumerate() {
+            let expected = blame_with_options(&revisions[..=k], options(algorithm)).unwrap();
+            assert_eq!(
+                attribution(snapshot),
+                attribution(&expected),
+                "revision {}",
+                k
+            );
+        }
+    });
+}
+
+#[test]
+fn test_snapshot_at_chosen_indices_keeps_request_order() {
+    let revisions = revisions(&HISTORY);
+
+    run_with_all_algorithms(|algorithm| {
+        let snapshots = blame_snapshots_at(&revisions, options(algorithm), &[3, 0, 3, 1]).unwrap();
+
+        assert_eq!(snapshots.len(), 4);
+        assert_eq!(
+            attribution(&snapshots[0]),
+            vec![("x\n", 2), ("a\n", 0), ("c\n", 0), ("d\n", 1)]
+        );
+        assert_eq!(
+            attribution(&snapshots[1]),
+            vec![("a\n", 0), ("b\n", 0), ("c\n", 0)]
+        );
+        assert_eq!(attribution(&snapshots[2]), attribution(&snapshots[0]));
+        assert_eq!(
+            attribution(&snapshots[3]),
+            vec![("a\n", 0), ("B\n", 1), ("c\n", 0), ("d\n", 1)]
+        );
+    });
+}
+
+#[test]
+fn test_snapshots_share_metadata() {
+    let revisions = revisions(&HISTORY);
+    let snapshots = blame_snapshots(&revisions, BlameOptions::default()).unwrap();
+
+    let line = snapshots[4].get_line(0).unwrap();
+    assert!(Rc::ptr_eq(&line.revision_metadata, &revisions[2].metadata));
+    assert!(Rc::ptr_eq(
+        &snapshots[2].get_line(0).unwrap().revision_metadata,
+        &line.revision_metadata
+    ));
+}
+
+#[test]
+fn test_no_indices_yields_no_snapshots() {
+    let revisions = revisions(&HISTORY);
+    let snapshots = blame_snapshots_at(&revisions, BlameOptions::default(), &[]).unwrap();
+    assert!(snapshots.is_empty());
+}
+
+#[test]
+fn test_out_of_range_index_is_an_error() {
+    let revisions = revisions(&HISTORY);
+
+    assert!(matches!(
+        blame_snapshots_at(&revisions, BlameOptions::default(), &[1, 5]),
+        Err(BlameError::RevisionOutOfRange {
+            revision_index: 5,
+            revisions: 5,
+        })
+    ));
+}
+
+#[test]
+fn test_empty_revisions() {
+    let revisions: Vec<BlameRevision<TestMetadata>> = Vec::new();
+
+    assert!(matches!(
+        blame_snapshots(&revisions, BlameOptions::default()),
+        Err(BlameError::EmptyRevisions)
+    ));
+}
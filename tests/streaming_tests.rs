@@ -0,0 +1,204 @@
+mod common;
+
+use blame_rs::{
+    BlameError, BlameOptions, BlameRevision, OwnedRevision, blame_iter, blame_with_options,
+};
+use common::{TestMetadata, load_fixture, run_with_all_algorithms};
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn to_revision(idx: usize, content: &str) -> BlameRevision<'_, TestMetadata> {
+    BlameRevision {
+        content,
+        metadata: Rc::new(TestMetadata { revision: idx }),
+    }
+}
+
+#[test]
+fn test_streaming_matches_slice_blame_on_fixtures() {
+    for fixture in ["line_modification", "multiple_revisions", "simple_add"] {
+        let contents = load_fixture(fixture);
+
+        run_with_all_algorithms(|algorithm| {
+            let options = BlameOptions {
+                algorithm,
+                ..Default::default()
+            };
+            let revisions: Vec<_> = contents
+                .iter()
+                .enumerate()
+                .map(|(idx, content)| to_revision(idx, content))
+                .collect();
+            let expected = blame_with_options(&revisions, options).unwrap();
+
+            let blamed = blame_iter(
+                contents
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, content)| to_revision(idx, content)),
+                options,
+            )
+            .unwrap();
+            let streamed = blamed.result();
+
+            assert_eq!(streamed.len(), expected.len(), "{}", fixture);
+            for (a, b) in streamed.iter().zip(expected.iter()) {
+                assert_eq!(a.content, b.content, "{}", fixture);
+                assert_eq!(a.revision_index, b.revision_index, "{}", fixture);
+                assert_eq!(a.revision_metadata.revision, b.revision_metadata.revision);
+            }
+        });
+    }
+}
+
+#[test]
+fn test_lazy_iterator_is_fully_consumed() {
+    let contents = ["a\n", "a\nb\n", "a\nb\nc\n", "b\nc\n"];
+    let produced = Cell::new(0);
+
+    let blamed = blame_iter(
+        contents.iter().enumerate().map(|(idx, &content)| {
+            produced.set(produced.get() + 1);
+            to_revision(idx, content)
+        }),
+        BlameOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(produced.get(), contents.len());
+    let attribution: Vec<(&str, usize)> = blamed
+        .result()
+        .iter()
+        .map(|line| (line.content, line.revision_metadata.revision))
+        .collect();
+    assert_eq!(attribution, vec![("b\n", 1), ("c\n", 2)]);
+}
+
+#[test]
+fn test_single_revision() {
+    let blamed = blame_iter([to_revision(0, "x\ny")], BlameOptions::default()).unwrap();
+    let result = blamed.result();
+    assert_eq!(result.len(), 2);
+    assert!(result.iter().all(|line| line.revision_index == 0));
+}
+
+#[test]
+fn test_empty_iterator() {
+    let result = blame_iter(
+        std::iter::empty::<BlameRevision<TestMetadata>>(),
+        BlameOptions::default(),
+    );
+    assert!(matches!(result, Err(BlameError::EmptyRevisions)));
+}
+
+/// Revision content that counts how many copies are alive
+struct Counted<'a> {
+    text: String,
+    alive: &'a Cell<usize>,
+}
+
+impl<'a> Counted<'a> {
+    fn new(text: &str, alive: &'a Cell<usize>) -> Self {
+        alive.set(alive.get() + 1);
+        Self {
+            text: text.to_string(),
+            alive,
+        }
+    }
+}
+
+impl AsRef<str> for Counted<'_> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.alive.set(self.alive.get() - 1);
+    }
+}
+
+#[test]
+fn test_owned_revisions_are_dropped_as_they_stream() {
+    let contents = ["a\nb\n", "a\nB\n", "a\nB\nc\n", "B\nc!\n"];
+    let alive = Cell::new(0);
+
+    let blamed = blame_iter(
+        contents.iter().enumerate().map(|(idx, content)| {
+            // Only the revision the next one is diffed against may still be alive
+            assert!(alive.get() <= 1, "{} revisions alive", alive.get());
+            OwnedRevision {
+                content: Counted::new(content, &alive),
+                metadata: Rc::new(TestMetadata { revision: idx }),
+            }
+        }),
+        BlameOptions {
+            similarity_threshold: Some(50),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // The result keeps the last revision, and copies of the lines it reports as replaced
+    assert_eq!(alive.get(), 1);
+    let result = blamed.result();
+    let attribution: Vec<(&str, usize)> = result
+        .iter()
+        .map(|line| (line.content, line.revision_metadata.revision))
+        .collect();
+    assert_eq!(attribution, vec![("B\n", 1), ("c!\n", 3)]);
+
+    let previous = result.lines()[1].previous.as_ref().unwrap();
+    assert_eq!(previous.content, "c\n");
+    assert_eq!((previous.revision_index, previous.line_number), (2, 2));
+    assert_eq!(previous.revision_metadata.revision, 2);
+
+    drop(result);
+    drop(blamed);
+    assert_eq!(alive.get(), 0);
+}
+
+#[test]
+fn test_streamed_previous_lines_match_slice_blame() {
+    let contents = load_fixture("line_modification");
+    let options = BlameOptions {
+        similarity_threshold: Some(40),
+        ..Default::default()
+    };
+    let revisions: Vec<_> = contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| to_revision(idx, content))
+        .collect();
+    let expected = blame_with_options(&revisions, options).unwrap();
+
+    let blamed = blame_iter(
+        contents
+            .iter()
+            .enumerate()
+            .map(|(idx, content)| OwnedRevision {
+                content: content.clone(),
+                metadata: Rc::new(TestMetadata { revision: idx }),
+            }),
+        options,
+    )
+    .unwrap();
+    let streamed = blamed.result();
+
+    assert!(expected.iter().any(|line| line.previous.is_some()));
+    assert_eq!(streamed.len(), expected.len());
+    for (a, b) in streamed.iter().zip(expected.iter()) {
+        assert_eq!(a.content, b.content);
+        assert_eq!(a.revision_index, b.revision_index);
+        let a_previous = a
+            .previous
+            .as_ref()
+            .map(|p| (p.revision_index, p.line_number, p.content));
+        let b_previous = b
+            .previous
+            .as_ref()
+            .map(|p| (p.revision_index, p.line_number, p.content));
+        assert_eq!(a_previous, b_previous);
+    }
+}
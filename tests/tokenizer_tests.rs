@@ -0,0 +1,85 @@
+mod common;
+
+use blame_rs::{
+    BlameOptions, CharTokenizer, LineTokenizer, Tokenizer, WordTokenizer, blame_with_options,
+    blame_with_tokenizer,
+};
+use common::revisions;
+
+fn sentences(content: &str) -> Vec<&str> {
+    content.split_inclusive(['.', '!', '?']).collect()
+}
+
+#[test]
+fn test_builtin_tokenizers_reproduce_content() {
+    let content = "fn main() {\r\n    let naïve = 1; // ok\n}";
+    let tokenizers: [&dyn Tokenizer; 3] = [&LineTokenizer, &WordTokenizer, &CharTokenizer];
+
+    for tokenizer in tokenizers {
+        let tokens = tokenizer.tokenize(content);
+        assert_eq!(tokens.concat(), content);
+        assert!(tokens.iter().all(|token| !token.is_empty()));
+    }
+
+    assert_eq!(
+        WordTokenizer.tokenize("let naïve = 1;\r\n"),
+        vec!["let", " ", "naïve", " ", "=", " ", "1", ";", "\r\n"]
+    );
+    assert_eq!(
+        LineTokenizer.tokenize("a\nb\r\nc"),
+        vec!["a\n", "b\r\n", "c"]
+    );
+}
+
+#[test]
+fn test_line_tokenizer_matches_default_blame() {
+    let revisions = revisions(&["a\nb\n", "a\nx\nb\n", "a\nx\nb\nc"]);
+
+    let default = blame_with_options(&revisions, BlameOptions::default()).expect("blame");
+    let lines = blame_with_tokenizer(&revisions, BlameOptions::default(), &LineTokenizer)
+        .expect("blame_with_tokenizer");
+
+    assert_eq!(default.len(), lines.len());
+    for (a, b) in default.iter().zip(lines.iter()) {
+        assert_eq!(a.content, b.content);
+        assert_eq!(a.revision_index, b.revision_index);
+    }
+}
+
+#[test]
+fn test_function_tokenizer_blames_sentences() {
+    let revisions = revisions(&[
+        "Blame is useful. It tracks lines.",
+        "Blame is useful. It tracks sentences too! Try it.",
+    ]);
+
+    let result = blame_with_tokenizer(&revisions, BlameOptions::default(), &sentences)
+        .expect("blame_with_tokenizer");
+
+    let units: Vec<(&str, usize)> = result
+        .iter()
+        .map(|unit| (unit.content, unit.revision_metadata.revision))
+        .collect();
+    assert_eq!(
+        units,
+        vec![
+            ("Blame is useful.", 0),
+            (" It tracks sentences too!", 1),
+            (" Try it.", 1),
+        ]
+    );
+}
+
+#[test]
+fn test_fn_pointer_tokenizer_is_accepted() {
+    let revisions = revisions(&["a,b,c", "a,c,d"]);
+    let fields: fn(&str) -> Vec<&str> = |content| content.split_inclusive(',').collect();
+
+    let result = blame_with_tokenizer(&revisions, BlameOptions::default(), &fields).expect("blame");
+
+    let units: Vec<(&str, usize)> = result
+        .iter()
+        .map(|unit| (unit.content, unit.revision_index))
+        .collect();
+    assert_eq!(units, vec![("a,", 0), ("c,", 1), ("d", 1)]);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use blame_rs::{BlameOptions, DiffAlgorithm, blame_with_options};
+use common::{indexed_revisions, run_with_all_algorithms};
+
+fn attribution(contents: &[String], algorithm: DiffAlgorithm) -> Vec<(String, usize)> {
+    let revisions = indexed_revisions(contents);
+    let options = BlameOptions {
+        algorithm,
+        ..Default::default()
+    };
+    blame_with_options(&revisions, options)
+        .unwrap()
+        .iter()
+        .map(|line| (line.content.to_string(), *line.revision_metadata))
+        .collect()
+}
+
+fn history(edits: &[&[&str]]) -> Vec<String> {
+    edits.iter().map(|lines| lines.concat()).collect()
+}
+
+#[test]
+fn test_edits_at_both_ends() {
+    let contents = history(&[
+        &["a\n", "b\n", "c\n", "d\n"],
+        &["start\n", "a\n", "b\n", "c\n", "d\n"],
+        &["start\n", "a\n", "b\n", "c\n", "d\n", "end\n"],
+        &["start\n", "a\n", "B\n", "c\n", "d\n", "end\n"],
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let expected: Vec<(String, usize)> = [
+            ("start\n", 1),
+            ("a\n", 0),
+            ("B\n", 3),
+            ("c\n", 0),
+            ("d\n", 0),
+            ("end\n", 2),
+        ]
+        .iter()
+        .map(|&(content, revision)| (content.to_string(), revision))
+        .collect();
+        assert_eq!(attribution(&contents, algorithm), expected);
+    });
+}
+
+#[test]
+fn test_prefix_and_suffix_overlap_on_repeated_lines() {
+    // The common prefix and suffix of "x x x" and "x x x x" overlap; the extra line must
+    // still be attributed exactly once
+    let contents = history(&[&["x\n", "x\n", "x\n"], &["x\n", "x\n", "x\n", "x\n"]]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = attribution(&contents, algorithm);
+        assert_eq!(result.len(), 4);
+        assert_eq!(
+            result.iter().filter(|(_, revision)| *revision == 1).count(),
+            1
+        );
+    });
+}
+
+#[test]
+fn test_readded_line_belongs_to_the_readding_revision() {
+    // Ids are numbered afresh every step, so the deleted line's old id can't carry its origin
+    // over: coming back makes it new
+    let contents = history(&[&["keep\n", "gone\n"], &["keep\n"], &["keep\n", "gone\n"]]);
+
+    run_with_all_algorithms(|algorithm| {
+        assert_eq!(
+            attribution(&contents, algorithm),
+            vec![("keep\n".to_string(), 0), ("gone\n".to_string(), 2)]
+        );
+    });
+}
+
+#[test]
+fn test_long_history_with_small_edits() {
+    let mut lines: Vec<String> = (0..2_000).map(|i| format!("line {}\n", i)).collect();
+    let mut contents = vec![lines.concat()];
+    for revision in 1..=50 {
+        let at = (revision * 37) % lines.len();
+        lines[at] = format!("edited in {}\n", revision);
+        contents.push(lines.concat());
+    }
+
+    run_with_all_algorithms(|algorithm| {
+        let result = attribution(&contents, algorithm);
+        let reconstructed: String = result.iter().map(|(content, _)| content.as_str()).collect();
+        assert_eq!(reconstructed, contents[50]);
+
+        for (content, revision) in &result {
+            match content.strip_prefix("edited in ") {
+                Some(edited) => assert_eq!(edited.trim_end(), revision.to_string()),
+                None => assert_eq!(*revision, 0),
+            }
+        }
+    });
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use blame_rs::{
+    BlameOptions, BlameRevision, ByteRevision, HistoryWarning, blame_bytes, blame_iter,
+    blame_snapshots, blame_with_options, blame_words,
+};
+use common::{TestMetadata, revisions};
+use std::rc::Rc;
+
+fn validating() -> BlameOptions {
+    BlameOptions {
+        validate: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_validation_is_off_by_default() {
+    let revisions = revisions(&["a\n", "a\n"]);
+    let result = blame_with_options(&revisions, BlameOptions::default()).unwrap();
+    assert!(result.warnings().is_empty());
+}
+
+#[test]
+fn test_clean_history_has_no_warnings() {
+    let revisions = revisions(&["a\n", "a\nb\n", "b\n", "a\nb\n"]);
+    let result = blame_with_options(&revisions, validating()).unwrap();
+    assert!(result.warnings().is_empty());
+}
+
+#[test]
+fn test_identical_consecutive_revisions() {
+    let revisions = revisions(&["a\n", "a\nb\n", "a\nb\n", "c\n", "c\n"]);
+    let result = blame_with_options(&revisions, validating()).unwrap();
+
+    assert_eq!(
+        result.warnings(),
+        &[
+            HistoryWarning::IdenticalToPrevious { revision_index: 2 },
+            HistoryWarning::IdenticalToPrevious { revision_index: 4 },
+        ]
+    );
+    // Attribution is unaffected
+    let attribution: Vec<usize> = result
+        .iter()
+        .map(|line| line.revision_metadata.revision)
+        .collect();
+    assert_eq!(attribution, vec![3]);
+}
+
+#[test]
+fn test_metadata_shared_across_distinct_contents() {
+    let shared = Rc::new(TestMetadata { revision: 0 });
+    let revisions = vec![
+        BlameRevision {
+            content: "a\n",
+            metadata: Rc::clone(&shared),
+        },
+        BlameRevision {
+            content: "a\nb\n",
+            metadata: Rc::new(TestMetadata { revision: 1 }),
+        },
+        BlameRevision {
+            content: "a\nb\nc\n",
+            metadata: Rc::clone(&shared),
+        },
+    ];
+
+    let result = blame_with_options(&revisions, validating()).unwrap();
+    assert_eq!(
+        result.warnings(),
+        &[HistoryWarning::SharedMetadata {
+            revision_index: 2,
+            shared_with: 0,
+        }]
+    );
+}
+
+#[test]
+fn test_duplicate_with_shared_metadata_is_only_identical() {
+    let metadata = Rc::new(TestMetadata { revision: 0 });
+    let revisions = vec![
+        BlameRevision {
+            content: "a\n",
+            metadata: Rc::clone(&metadata),
+        },
+        BlameRevision {
+            content: "a\n",
+            metadata,
+        },
+    ];
+
+    let result = blame_with_options(&revisions, validating()).unwrap();
+    assert_eq!(
+        result.warnings(),
+        &[HistoryWarning::IdenticalToPrevious { revision_index: 1 }]
+    );
+}
+
+#[test]
+fn test_warnings_in_other_modes() {
+    let contents = ["a b\n", "a b\n", "a c\n"];
+    let revisions = revisions(&contents);
+    let expected = [HistoryWarning::IdenticalToPrevious { revision_index: 1 }];
+
+    assert_eq!(
+        blame_words(&revisions, validating()).unwrap().warnings(),
+        &expected
+    );
+    assert_eq!(
+        blame_iter(
+            revisions.iter().map(|revision| BlameRevision {
+                content: revision.content,
+                metadata: Rc::clone(&revision.metadata),
+            }),
+            validating()
+        )
+        .unwrap()
+        .result()
+        .warnings(),
+        &expected
+    );
+
+    let snapshots = blame_snapshots(&revisions, validating()).unwrap();
+    assert!(snapshots[0].warnings().is_empty());
+    assert_eq!(snapshots[2].warnings(), &expected);
+
+    let byte_revisions: Vec<ByteRevision<TestMetadata>> = contents
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| ByteRevision {
+            content: content.as_bytes(),
+            metadata: Rc::new(TestMetadata { revision: idx }),
+        })
+        .collect();
+    assert_eq!(
+        blame_bytes(&byte_revisions, validating())
+            .unwrap()
+            .warnings(),
+        &expected
+    );
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use blame_rs::{BlameOptions, WordBlameLine, blame_words};
+use common::{TestMetadata, revisions, run_with_all_algorithms};
+
+fn spans_of<'a>(line: &WordBlameLine<'a, TestMetadata>) -> Vec<(&'a str, usize)> {
+    line.spans
+        .iter()
+        .map(|span| (span.content, span.revision_metadata.revision))
+        .collect()
+}
+
+#[test]
+fn test_typo_fix_only_claims_the_word() {
+    let revisions = revisions(&[
+        "The quick brown fox jumps over the lazy dgo.\nSecond line.\n",
+        "The quick brown fox jumps over the lazy dog.\nSecond line.\n",
+    ]);
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_words(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("blame_words");
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            spans_of(result.get_line(0).expect("line 0")),
+            vec![
+                ("The quick brown fox jumps over the lazy ", 0),
+                ("dog", 1),
+                (".\n", 0),
+            ]
+        );
+        assert_eq!(
+            spans_of(result.get_line(1).expect("line 1")),
+            vec![("Second line.\n", 0)]
+        );
+    });
+}
+
+#[test]
+fn test_spans_cover_final_content() {
+    let revisions = revisions(&[
+        "alpha beta\r\ngamma",
+        "alpha, beta\r\ngamma delta",
+        "über alpha, beta\r\ngamma delta\n",
+    ]);
+    let content = revisions[2].content;
+
+    run_with_all_algorithms(|algorithm| {
+        let result = blame_words(
+            &revisions,
+            BlameOptions {
+                algorithm,
+                ..Default::default()
+            },
+        )
+        .expect("blame_words");
+
+        let lines: Vec<&str> = result.iter().map(|line| line.content).collect();
+        assert_eq!(lines, vec!["über alpha, beta\r\n", "gamma delta\n"]);
+
+        let mut offset = 0;
+        for (idx, line) in result.iter().enumerate() {
+            assert_eq!(line.line_number, idx);
+            assert_eq!(
+                line.spans
+                    .iter()
+                    .map(|span| span.content)
+                    .collect::<String>(),
+                line.content
+            );
+            for span in &line.spans {
+                assert_eq!(span.range.start, offset);
+                assert_eq!(&content[span.range.clone()], span.content);
+                offset = span.range.end;
+            }
+        }
+        assert_eq!(offset, content.len());
+
+        let first = result.get_line(0).expect("line 0");
+        assert_eq!(first.spans[0].content, "über ");
+        assert_eq!(first.spans[0].revision_index, 2);
+    });
+}
+
+#[test]
+fn test_single_revision_has_one_span_per_line() {
+    let revisions = revisions(&["one two\nthree four\n"]);
+
+    let result = blame_words(&revisions, BlameOptions::default()).expect("blame_words");
+    assert_eq!(result.len(), 2);
+    for line in result.iter() {
+        assert_eq!(line.spans.len(), 1);
+        assert_eq!(line.spans[0].revision_index, 0);
+    }
+}
+
+#[test]
+fn test_empty_final_revision_has_no_lines() {
+    let revisions = revisions(&["some text\n", ""]);
+
+    let result = blame_words(&revisions, BlameOptions::default()).expect("blame_words");
+    assert!(result.is_empty());
+}